@@ -65,6 +65,7 @@ export default async function handler(_req: VercelRequest, res: VercelResponse)
       .map((e: any) => {
         const allianceId = e.topic?.[1]?.replace(/^"|"$/g, '') || 'unknown';
         const submissionId = e.topic?.[2] || '';
+        const submitter = e.topic?.[3] ? String(e.topic[3]).replace(/^"|"$/g, '') : null;
         const dataParts = (e.value || '').replace(/^\(|\)$/g, '').split(',');
         const passed = dataParts[0]?.trim() === 'true';
         const threshold = parseInt(dataParts[1]?.trim() || '0', 10);
@@ -74,6 +75,7 @@ export default async function handler(_req: VercelRequest, res: VercelResponse)
         return {
           allianceId,
           submissionId: typeof submissionId === 'string' ? submissionId.replace(/^"|"$/g, '') : String(submissionId),
+          submitter,
           passed,
           threshold,
           ledger,
@@ -1,12 +1,14 @@
 import type { VercelRequest, VercelResponse } from '@vercel/node';
-import crypto from 'crypto';
 
 import {
+  Account,
+  Address,
   Contract,
   Keypair,
   nativeToScVal,
   Networks,
   rpc,
+  scValToNative,
   TransactionBuilder,
 } from '@stellar/stellar-sdk';
 
@@ -16,6 +18,11 @@ const CONTRACT_ID =
 const STELLAR_NETWORK = process.env.STELLAR_NETWORK || 'testnet';
 const STELLAR_SECRET_KEY = process.env.STELLAR_SECRET_KEY || '';
 
+// Per-transaction resource limits a proof's estimated cost must fit in
+// (Soroban's network limits by default).
+const MAX_CPU_INSNS = BigInt(process.env.STELLAR_MAX_CPU_INSNS || '100000000');
+const MAX_MEM_BYTES = BigInt(process.env.STELLAR_MAX_MEM_BYTES || '41943040');
+
 const RPC_URLS: Record<string, string> = {
   testnet: 'https://soroban-testnet.stellar.org',
   mainnet: 'https://soroban-mainnet.stellar.org',
@@ -28,11 +35,40 @@ const NETWORK_PASSPHRASES: Record<string, string> = {
   futurenet: Networks.FUTURENET,
 };
 
+/**
+ * Word order of each circuit's public inputs, as defined by the verifier
+ * crate's `public_inputs` module (see "Public-input layouts" in
+ * docs/DEPLOYMENT.md). Keep in step with it.
+ */
+const PUBLIC_INPUT_LAYOUTS: Record<string, string[]> = {
+  benchmark_delta: ['submission_commitment', 'passed', 'min_improvement_percent', 'anchor_ledger'],
+  data_completeness: ['minimum_required', 'submission_commitment', 'passed', 'anchor_ledger'],
+  execution_duration: ['min_days', 'max_days', 'submission_commitment', 'passed', 'anchor_ledger'],
+  resource_range: ['min_cost_cents', 'max_cost_cents', 'submission_commitment', 'passed', 'anchor_ledger'],
+};
+
+/**
+ * The integer in the low four bytes of `circuit`'s public input `name`, or 0
+ * if the circuit has no such input.
+ */
+function readWord(publicInputs: Uint8Array, circuit: string, name: string): number {
+  const index = PUBLIC_INPUT_LAYOUTS[circuit]?.indexOf(name) ?? -1;
+  if (index < 0 || publicInputs.length < (index + 1) * 32) return 0;
+  const end = (index + 1) * 32;
+  return new DataView(publicInputs.buffer, publicInputs.byteOffset + end - 4, 4).getUint32(0);
+}
+
 interface ProveRequest {
   verificationId: string;
   optimizationLogId: string;
   circuit: string;
   allianceId?: string;
+  /**
+   * Hex submission id (32 bytes). The proof's `submission_commitment` public
+   * input must have been computed over this id, the alliance id and the
+   * relay's signing address — see "Submission binding" in docs/DEPLOYMENT.md.
+   */
+  submissionId?: string;
   /** Base64-encoded UltraHonk proof bytes (generated in browser via bb.js) */
   proofBytes?: string;
   /** Base64-encoded public inputs (32-byte field elements, concatenated) */
@@ -40,41 +76,95 @@ interface ProveRequest {
   contractId?: string;
 }
 
+/** The contract's conservative bound for one `verify_and_attest` call. */
+interface VerifyCost {
+  cpuInsns: string;
+  memBytes: string;
+}
+
 interface ProveResponse {
   status: 'verified' | 'failed' | 'pending';
   txHash?: string;
   error?: string;
+  estimatedCost?: VerifyCost;
   attestation?: {
     submissionId: string;
     allianceId: string;
+    submitter: string;
     passed: boolean;
     threshold: number;
   };
 }
 
-function generateSubmissionId(optimizationLogId: string, params: Record<string, string>): string {
-  return crypto
-    .createHash('sha256')
-    .update(optimizationLogId)
-    .update(JSON.stringify(params))
-    .update(crypto.randomBytes(8).toString('hex'))
-    .digest('hex');
-}
-
 function base64ToUint8Array(b64: string): Uint8Array {
   return new Uint8Array(Buffer.from(b64, 'base64'));
 }
 
+/** The proof's estimated cost exceeds what one transaction may spend. */
+class ProofTooExpensiveError extends Error {
+  constructor(readonly cost: VerifyCost) {
+    super(
+      `Proof needs an estimated ${cost.cpuInsns} CPU instructions and ${cost.memBytes} ` +
+        `bytes of memory, over the per-transaction limit of ${MAX_CPU_INSNS} / ` +
+        `${MAX_MEM_BYTES}. Send it uncompressed, or verify it in steps with ` +
+        'open_session / advance_session.',
+    );
+  }
+}
+
+/**
+ * Ask the contract (by simulation, so nothing is submitted) for its
+ * conservative cost bound for verifying a `proofLength`-byte proof of
+ * `circuit`.
+ */
+async function estimateVerifyCost(
+  server: rpc.Server,
+  contract: Contract,
+  source: Account,
+  passphrase: string,
+  circuit: string,
+  proofLength: number,
+): Promise<VerifyCost> {
+  // A copy, since building a transaction bumps the account's sequence
+  // number and this one is never submitted.
+  const account = new Account(source.accountId(), source.sequenceNumber());
+  const transaction = new TransactionBuilder(account, {
+    fee: '100',
+    networkPassphrase: passphrase,
+  })
+    .addOperation(
+      contract.call(
+        'estimate_verify_cost',
+        nativeToScVal(circuit, { type: 'symbol' }),
+        nativeToScVal(proofLength, { type: 'u32' }),
+      ),
+    )
+    .setTimeout(30)
+    .build();
+
+  const sim = await server.simulateTransaction(transaction);
+  if (rpc.Api.isSimulationError(sim)) {
+    throw new Error(`Cost estimate failed: ${sim.error.slice(0, 300)}`);
+  }
+  const retval = sim.result?.retval;
+  if (!retval) {
+    throw new Error('Cost estimate returned no value');
+  }
+  const cost = scValToNative(retval) as { cpu_insns: bigint; mem_bytes: bigint };
+  return { cpuInsns: cost.cpu_insns.toString(), memBytes: cost.mem_bytes.toString() };
+}
+
 /**
  * Submit a pre-generated UltraHonk proof to the Soroban attestation contract
  * using @stellar/stellar-sdk (no stellar CLI needed).
  */
 async function verifyAndAttestOnChain(
+  circuit: string,
   allianceId: string,
   submissionIdHex: string,
   proofBytes: Uint8Array,
   publicInputsBytes: Uint8Array,
-): Promise<{ txHash: string }> {
+): Promise<{ txHash: string; submitter: string; estimatedCost: VerifyCost }> {
   if (!STELLAR_SECRET_KEY) {
     throw new Error('STELLAR_SECRET_KEY env var not set — cannot sign Soroban transactions');
   }
@@ -86,10 +176,30 @@ async function verifyAndAttestOnChain(
   const server = new rpc.Server(rpcUrl);
   const sourceKeypair = Keypair.fromSecret(STELLAR_SECRET_KEY);
   const sourceAccount = await server.getAccount(sourceKeypair.publicKey());
+  const contract = new Contract(contractId);
+
+  // Turn away proofs that cannot fit in one transaction rather than submit
+  // one that runs out of budget (and still pays its fee).
+  const estimatedCost = await estimateVerifyCost(
+    server,
+    contract,
+    sourceAccount,
+    passphrase,
+    circuit,
+    proofBytes.length,
+  );
+  if (
+    BigInt(estimatedCost.cpuInsns) > MAX_CPU_INSNS ||
+    BigInt(estimatedCost.memBytes) > MAX_MEM_BYTES
+  ) {
+    throw new ProofTooExpensiveError(estimatedCost);
+  }
 
   // Build ScVal arguments for verify_and_attest:
+  //   circuit: Symbol
   //   alliance_id: String
   //   submission_id: BytesN<32>
+  //   submitter: Address (the relay's signing account)
   //   public_inputs: Bytes
   //   proof_bytes: Bytes
   const submissionIdBytes = new Uint8Array(
@@ -97,14 +207,14 @@ async function verifyAndAttestOnChain(
   );
 
   const args = [
+    nativeToScVal(circuit, { type: 'symbol' }),
     nativeToScVal(allianceId, { type: 'string' }),
     nativeToScVal(submissionIdBytes, { type: 'bytes' }),
+    new Address(sourceKeypair.publicKey()).toScVal(),
     nativeToScVal(publicInputsBytes, { type: 'bytes' }),
     nativeToScVal(proofBytes, { type: 'bytes' }),
   ];
 
-  const contract = new Contract(contractId);
-
   const transaction = new TransactionBuilder(sourceAccount, {
     fee: '10000000',
     networkPassphrase: passphrase,
@@ -141,7 +251,7 @@ async function verifyAndAttestOnChain(
     );
   }
 
-  return { txHash: sentTx.hash };
+  return { txHash: sentTx.hash, submitter: sourceKeypair.publicKey(), estimatedCost };
 }
 
 export default async function handler(req: VercelRequest, res: VercelResponse) {
@@ -170,30 +280,39 @@ export default async function handler(req: VercelRequest, res: VercelResponse) {
       });
     }
 
+    // The proof is bound to its submission id, so the id must come from the
+    // client that computed the submission commitment before proving.
+    const submissionId = body.submissionId?.replace(/^0x/, '') || '';
+    if (!/^[0-9a-fA-F]{64}$/.test(submissionId)) {
+      return res.status(400).json({
+        status: 'failed',
+        error: 'Missing or malformed submissionId (expected 32-byte hex).',
+      });
+    }
+
     const allianceId = body.allianceId || `alliance:${body.circuit}`;
-    const submissionId = generateSubmissionId(body.optimizationLogId, {
-      proofLen: String(proofBytes.length),
-      piLen: String(publicInputsBytes.length),
-    });
 
-    const { txHash } = await verifyAndAttestOnChain(
+    const { txHash, submitter, estimatedCost } = await verifyAndAttestOnChain(
+      body.circuit,
       allianceId,
       submissionId,
       proofBytes,
       publicInputsBytes,
     );
 
-    // Parse public outputs from the public inputs bytes
-    // [0..32] → passed, [32..64] → threshold
-    const passed = publicInputsBytes[31] !== 0;
-    const threshold = publicInputsBytes[63] || 0;
+    // Parse public outputs from the public inputs bytes (the contract has
+    // already range-checked them against the circuit's layout).
+    const passed = readWord(publicInputsBytes, body.circuit, 'passed') === 1;
+    const threshold = readWord(publicInputsBytes, body.circuit, 'min_improvement_percent');
 
     const response: ProveResponse = {
       status: 'verified',
       txHash,
+      estimatedCost,
       attestation: {
         submissionId,
         allianceId,
+        submitter,
         passed,
         threshold,
       },
@@ -201,6 +320,13 @@ export default async function handler(req: VercelRequest, res: VercelResponse) {
 
     return res.status(200).json(response);
   } catch (error) {
+    if (error instanceof ProofTooExpensiveError) {
+      return res.status(422).json({
+        status: 'failed',
+        error: error.message,
+        estimatedCost: error.cost,
+      });
+    }
     console.error('Stellar prove error:', error);
     return res.status(500).json({
       status: 'failed',
@@ -21,6 +21,16 @@
 const CASE_STUDY_DBC_MINT = new web3.PublicKey("8aNpSwFq7idN5LsX27wHndmfe46ApQkps9PgnSCLGwVT"); // Devnet token
 const CASE_STUDY_TREASURY_PROGRAM = new web3.PublicKey("C5UAymmKGderVikGFiLJY88X3ZL5C49eEKTVdkKxh6nk");
 
+// Noir proofs (UltraHonk, bb v0.87.0) are uploaded in chunks and verified on-chain
+const PROOF_BYTES = 14592;
+const SESSION_BYTES = 3170; // Verifier state kept between verification steps
+const PROOF_BUFFER_SPACE = 8 + 32 + 8 + 4 + 4 + 32 + 1 + 5 + SESSION_BYTES + PROOF_BYTES;
+const PROOF_BUFFER_VERIFIED = 2;
+const SUMCHECK_ROUNDS_PER_TX = 4;
+const UPLOAD_CHUNK_BYTES = 900; // Fits one transaction
+const ENCRYPTION_CIRCUIT_ID = Array.from(Buffer.from("encr"));
+const VALIDATION_CIRCUIT_ID = Array.from(Buffer.from("noir"));
+
 // ============================================================================
 // HELPER FUNCTIONS
 // ============================================================================
@@ -46,6 +56,13 @@ function getOptimizationLogPDA(submitter, timestamp, programId) {
   );
 }
 
+function getNoirVkPDA(noirCircuitId, programId) {
+  return web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("noir_vk"), Buffer.from(noirCircuitId)],
+    programId
+  );
+}
+
 function getValidationPDA(optimizationLog, validator, programId) {
   return web3.PublicKey.findProgramAddressSync(
     [
@@ -61,15 +78,94 @@ function getValidationPDA(optimizationLog, validator, programId) {
 // INSTRUCTIONS
 // ============================================================================
 
+/**
+ * Register a Noir circuit's verification key (governance authority only)
+ */
+async function registerNoirVk(noirCircuitId, vk, governanceConfig) {
+  const [noirVkPDA] = getNoirVkPDA(noirCircuitId, pg.program.programId);
+  const accounts = {
+    noirVk: noirVkPDA,
+    governanceConfig,
+    governanceAuthority: pg.wallet.publicKey,
+  };
+
+  await pg.program.methods
+    .initNoirVk(noirCircuitId)
+    .accounts({ ...accounts, systemProgram: web3.SystemProgram.programId })
+    .rpc();
+  for (let offset = 0; offset < vk.length; offset += UPLOAD_CHUNK_BYTES) {
+    await pg.program.methods
+      .writeNoirVk(offset, Buffer.from(vk.slice(offset, offset + UPLOAD_CHUNK_BYTES)))
+      .accounts(accounts)
+      .rpc();
+  }
+  const tx = await pg.program.methods.finalizeNoirVk().accounts(accounts).rpc();
+
+  console.log("✅ Noir VK registered:", noirVkPDA.toString());
+  return { tx, noirVkPDA };
+}
+
+/**
+ * Upload a Noir proof and verify it on-chain
+ * Returns the proof buffer account to pass to the instruction that consumes it
+ */
+async function uploadAndVerifyProof(noirCircuitId, proof, publicInput) {
+  const proofBuffer = web3.Keypair.generate();
+  const owner = pg.wallet.publicKey;
+  const [noirVkPDA] = getNoirVkPDA(noirCircuitId, pg.program.programId);
+  const maxComputeUnits = web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 });
+
+  // Too large for an `init` constraint: allocate it here, the program takes ownership
+  const rent = await pg.connection.getMinimumBalanceForRentExemption(PROOF_BUFFER_SPACE);
+  await pg.program.methods
+    .initProofBuffer(noirCircuitId, publicInput)
+    .accounts({ proofBuffer: proofBuffer.publicKey, owner })
+    .preInstructions([
+      web3.SystemProgram.createAccount({
+        fromPubkey: owner,
+        newAccountPubkey: proofBuffer.publicKey,
+        lamports: rent,
+        space: PROOF_BUFFER_SPACE,
+        programId: pg.program.programId,
+      }),
+    ])
+    .signers([proofBuffer])
+    .rpc();
+
+  for (let offset = 0; offset < proof.length; offset += UPLOAD_CHUNK_BYTES) {
+    await pg.program.methods
+      .writeProofChunk(offset, Buffer.from(proof.slice(offset, offset + UPLOAD_CHUNK_BYTES)))
+      .accounts({ proofBuffer: proofBuffer.publicKey, owner })
+      .rpc();
+  }
+
+  // One verifier step per transaction: transcript, sumcheck rounds, relations, Shplemini, pairing
+  let tx;
+  let status;
+  do {
+    tx = await pg.program.methods
+      .verifyProofBuffer(SUMCHECK_ROUNDS_PER_TX)
+      .accounts({ proofBuffer: proofBuffer.publicKey, noirVk: noirVkPDA, owner })
+      .preInstructions([maxComputeUnits])
+      .rpc();
+    ({ status } = await pg.program.account.proofBuffer.fetch(proofBuffer.publicKey));
+  } while (status !== PROOF_BUFFER_VERIFIED);
+
+  console.log("✅ Noir proof verified:", proofBuffer.publicKey.toString());
+  return { tx, proofBuffer: proofBuffer.publicKey };
+}
+
 /**
  * Submit an encrypted case study
+ * `encryptionProof` is a proof buffer verified by `uploadAndVerifyProof` for
+ * ENCRYPTION_CIRCUIT_ID with `metadataHash` as its public input
  */
 async function submitOptimizationLog(
   ipfsCid,
   metadataHash, // 32 bytes
   optimizationCategory, // 0=experimental, 1=approved, 2=alternative
   executionDuration,
-  proofOfEncryption,
+  encryptionProof,
   lightProtocolProof,
   compressionRatio
 ) {
@@ -89,12 +185,12 @@ async function submitOptimizationLog(
       metadataHash,
       optimizationCategory,
       executionDuration,
-      proofOfEncryption,
       lightProtocolProof,
       compressionRatio
     )
     .accounts({
       optimizationLog: optimizationLogPDA,
+      encryptionProof,
       submitter: pg.wallet.publicKey,
       dbcMint: CASE_STUDY_DBC_MINT,
       systemProgram: web3.SystemProgram.programId,
@@ -188,13 +284,18 @@ console.log("Wallet:", pg.wallet.publicKey.toString());
 // Uncomment ONE of these inside the run() function:
 
 async function runOptimizationLog() {
-  // 1. Submit case study
+  // 1. Submit case study (proof and metadata hash from the Noir encryption circuit)
+  // const { proofBuffer } = await uploadAndVerifyProof(
+  //   ENCRYPTION_CIRCUIT_ID,
+  //   encryptionProofBytes, // bb `prove` output
+  //   metadataHash
+  // );
   // await submitOptimizationLog(
   //   "QmExample123456789", // IPFS CID
-  //   new Array(32).fill(1), // metadata hash (32 bytes)
+  //   metadataHash, // metadata hash (32 bytes)
   //   0, // treatment category (0=experimental)
   //   30, // duration days
-  //   new Array(64).fill(2), // proof of encryption
+  //   proofBuffer, // verified proof of encryption
   //   new Array(128).fill(3), // light protocol proof
   //   10 // compression ratio
   // );
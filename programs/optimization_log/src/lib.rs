@@ -1,6 +1,10 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Mint};
 use anchor_spl::token_2022::Token2022;
+use ultrahonk_soroban_verifier::{
+    types::PAIRING_POINTS_SIZE, BbVersion, SessionStep, Solana, UltraHonkVerifier,
+    VerificationSession, VerifyError, PROOF_BYTES, SESSION_BYTES,
+};
 
 // DBC Treasury Program Integration
 // The treasury handles all DBC token operations (rewards, staking, slashing)
@@ -25,7 +29,6 @@ pub mod optimization_log {
         metadata_hash: [u8; 32],             // Hash of encrypted metadata
         optimization_category: u8,              // 0=prompt_engineering, 1=fine_tuning, 2=agent_architecture
         execution_duration: u16,
-        proof_of_encryption: Vec<u8>,        // ZK proof that data is properly encrypted
         light_protocol_proof: Vec<u8>,       // Light Protocol ZK compression proof
         compression_ratio: u16,             // Ratio achieved (e.g., 10x compression)
     ) -> Result<()> {
@@ -46,11 +49,28 @@ pub mod optimization_log {
             OptimizationLogError::InvalidCompressionRatio
         );
 
-        // Verify ZK proof of proper encryption (Noir circuit)
-        require!(
-            proof_of_encryption.len() > 0,
-            OptimizationLogError::MissingEncryptionProof
-        );
+        // ZK proof of proper encryption (Noir circuit), verified beforehand
+        // in `encryption_proof`; its public input commits to the metadata
+        let encryption_proof_hash = {
+            let encryption_proof = ctx.accounts.encryption_proof.load()?;
+            require!(
+                encryption_proof.owner == ctx.accounts.submitter.key(),
+                OptimizationLogError::UnauthorizedProofBuffer
+            );
+            require!(
+                encryption_proof.noir_circuit_id == ENCRYPTION_CIRCUIT_ID,
+                OptimizationLogError::InvalidCircuitId
+            );
+            require!(
+                encryption_proof.status == PROOF_BUFFER_VERIFIED,
+                OptimizationLogError::MissingEncryptionProof
+            );
+            require!(
+                encryption_proof.public_input == metadata_hash,
+                OptimizationLogError::PublicInputMismatch
+            );
+            hash(&encryption_proof.proof)
+        };
 
         // Verify Light Protocol compression proof
         require!(
@@ -107,6 +127,7 @@ pub mod optimization_log {
             created_at: clock.unix_timestamp,
             light_proof_hash,
             compression_ratio,
+            encryption_proof_hash,
         });
 
         Ok(())
@@ -117,7 +138,6 @@ pub mod optimization_log {
     pub fn validator_prove_integrity(
         ctx: Context<ValidateWithProof>,
         validation_type: ValidationType,
-        proof: Vec<u8>,                      // Noir ZK-SNARK proof
         public_inputs: [u8; 32],             // Public commitments (no private data)
         stake_amount: u64,
         noir_circuit_id: [u8; 4],            // Specific Noir circuit used
@@ -127,10 +147,6 @@ pub mod optimization_log {
             stake_amount >= MINIMUM_VALIDATOR_STAKE,
             OptimizationLogError::InsufficientStake
         );
-        require!(
-            proof.len() > 0,
-            OptimizationLogError::InvalidProof
-        );
         require!(
             noir_circuit_id != [0, 0, 0, 0],
             OptimizationLogError::InvalidCircuitId
@@ -144,18 +160,37 @@ pub mod optimization_log {
 
         // Verify Noir circuit ID is valid for this validation type
         let expected_circuit = match validation_type {
-            ValidationType::Approve => [b'n', b'o', b'i', b'r'], // "noir" circuit
-            ValidationType::Reject => [b'n', b'o', b'i', b'r'], // Same circuit for now
-            ValidationType::FurtherReview => [b'n', b'o', b'i', b'r'],
+            ValidationType::Approve => VALIDATION_CIRCUIT_ID,
+            ValidationType::Reject => VALIDATION_CIRCUIT_ID, // Same circuit for now
+            ValidationType::FurtherReview => VALIDATION_CIRCUIT_ID,
         };
         require!(
             noir_circuit_id == expected_circuit,
             OptimizationLogError::InvalidCircuitForValidationType
         );
 
-        // In production, verify Noir proof on-chain using Aztec verifier
-        // For now, we trust the proof format is correct
-        let proof_hash = hash(&proof);
+        // Noir proof, verified beforehand in `proof_buffer` against the
+        // VK registered for `noir_circuit_id`
+        let proof_hash = {
+            let proof_buffer = ctx.accounts.proof_buffer.load()?;
+            require!(
+                proof_buffer.owner == ctx.accounts.validator.key(),
+                OptimizationLogError::UnauthorizedProofBuffer
+            );
+            require!(
+                proof_buffer.noir_circuit_id == noir_circuit_id,
+                OptimizationLogError::InvalidCircuitId
+            );
+            require!(
+                proof_buffer.status == PROOF_BUFFER_VERIFIED,
+                OptimizationLogError::ProofNotVerified
+            );
+            require!(
+                proof_buffer.public_input == public_inputs,
+                OptimizationLogError::PublicInputMismatch
+            );
+            hash(&proof_buffer.proof)
+        };
         let noir_verification_hash = hash(&[proof_hash.as_ref(), &circuit_params_hash].concat());
         
         // Check if validator already validated this case study (Sybil resistance)
@@ -510,6 +545,191 @@ pub mod optimization_log {
 
         Ok(())
     }
+
+    /// Register the UltraHonk verification key for a Noir circuit (governance only)
+    /// The VK is larger than one transaction, so it is uploaded with
+    /// `write_noir_vk` and locked by `finalize_noir_vk`
+    pub fn init_noir_vk(
+        ctx: Context<InitNoirVk>,
+        noir_circuit_id: [u8; 4],
+    ) -> Result<()> {
+        require!(
+            noir_circuit_id != [0, 0, 0, 0],
+            OptimizationLogError::InvalidCircuitId
+        );
+
+        let noir_vk = &mut ctx.accounts.noir_vk;
+        noir_vk.noir_circuit_id = noir_circuit_id;
+        noir_vk.vk = Vec::new();
+        noir_vk.is_finalized = false;
+        noir_vk.bump = ctx.bumps.noir_vk;
+
+        Ok(())
+    }
+
+    /// Append the next chunk of `bb write_vk` output
+    pub fn write_noir_vk(
+        ctx: Context<WriteNoirVk>,
+        offset: u32,
+        chunk: Vec<u8>,
+    ) -> Result<()> {
+        let noir_vk = &mut ctx.accounts.noir_vk;
+        require!(
+            !noir_vk.is_finalized,
+            OptimizationLogError::NoirVkFinalized
+        );
+        require!(
+            offset as usize == noir_vk.vk.len() && noir_vk.vk.len() + chunk.len() <= NOIR_VK_BYTES,
+            OptimizationLogError::ChunkOutOfOrder
+        );
+
+        noir_vk.vk.extend_from_slice(&chunk);
+
+        Ok(())
+    }
+
+    /// Parse the uploaded VK and lock it
+    /// Circuits verified here take exactly one public input
+    pub fn finalize_noir_vk(ctx: Context<WriteNoirVk>) -> Result<()> {
+        let noir_vk = &mut ctx.accounts.noir_vk;
+        require!(
+            !noir_vk.is_finalized,
+            OptimizationLogError::NoirVkFinalized
+        );
+
+        let verifier = UltraHonkVerifier::new(&Solana, &noir_vk.vk)
+            .map_err(|_| OptimizationLogError::InvalidVerificationKey)?;
+        require!(
+            verifier.get_vk().public_inputs_size == PAIRING_POINTS_SIZE as u64 + 1,
+            OptimizationLogError::InvalidVerificationKey
+        );
+
+        noir_vk.is_finalized = true;
+
+        emit!(NoirVkRegistered {
+            noir_circuit_id: noir_vk.noir_circuit_id,
+            vk_hash: hash(&noir_vk.vk),
+        });
+
+        Ok(())
+    }
+
+    /// Start uploading a Noir proof
+    /// `proof_buffer` is created by the client with `SystemProgram.createAccount`
+    /// (`PROOF_BUFFER_SPACE` bytes, owned by this program): it is larger than
+    /// the 10 KiB an `init` constraint can allocate
+    pub fn init_proof_buffer(
+        ctx: Context<InitProofBuffer>,
+        noir_circuit_id: [u8; 4],
+        public_input: [u8; 32],
+    ) -> Result<()> {
+        let mut proof_buffer = ctx.accounts.proof_buffer.load_init()?;
+        proof_buffer.owner = ctx.accounts.owner.key();
+        proof_buffer.expires_at = Clock::get()?.slot + PROOF_BUFFER_TTL_SLOTS;
+        proof_buffer.noir_circuit_id = noir_circuit_id;
+        proof_buffer.status = PROOF_BUFFER_WRITING;
+        proof_buffer.written = 0;
+        proof_buffer.public_input = public_input;
+
+        Ok(())
+    }
+
+    /// Append the next chunk of proof bytes
+    pub fn write_proof_chunk(
+        ctx: Context<UseProofBuffer>,
+        offset: u32,
+        chunk: Vec<u8>,
+    ) -> Result<()> {
+        let mut proof_buffer = ctx.accounts.proof_buffer.load_mut()?;
+        require!(
+            proof_buffer.status == PROOF_BUFFER_WRITING,
+            OptimizationLogError::ProofBufferNotWritable
+        );
+        let start = proof_buffer.written as usize;
+        let end = start + chunk.len();
+        require!(
+            offset as usize == start && end <= PROOF_BYTES,
+            OptimizationLogError::ChunkOutOfOrder
+        );
+
+        proof_buffer.proof[start..end].copy_from_slice(&chunk);
+        proof_buffer.written = end as u32;
+
+        Ok(())
+    }
+
+    /// Run the next step of verifying an uploaded proof: the transcript, up
+    /// to `max_rounds` sumcheck rounds, the relations, Shplemini or the
+    /// pairing (see `ultrahonk_soroban_verifier::session`). Each step fits
+    /// one transaction at the maximum compute unit limit; call until the
+    /// buffer is `PROOF_BUFFER_VERIFIED`, before `expires_at`
+    pub fn verify_proof_buffer(ctx: Context<VerifyProofBuffer>, max_rounds: u32) -> Result<()> {
+        let mut proof_buffer = ctx.accounts.proof_buffer.load_mut()?;
+        require!(
+            Clock::get()?.slot <= proof_buffer.expires_at,
+            OptimizationLogError::ProofBufferExpired
+        );
+        require!(
+            proof_buffer.written as usize == PROOF_BYTES,
+            OptimizationLogError::ProofIncomplete
+        );
+
+        let noir_vk = &ctx.accounts.noir_vk;
+        require!(
+            noir_vk.is_finalized && noir_vk.noir_circuit_id == proof_buffer.noir_circuit_id,
+            OptimizationLogError::NoirVkNotRegistered
+        );
+
+        // The first call locks the upload and binds the session to it
+        let mut session = match proof_buffer.status {
+            PROOF_BUFFER_WRITING => VerificationSession::new(
+                &Solana,
+                &proof_buffer.proof,
+                &proof_buffer.public_input,
+            ),
+            PROOF_BUFFER_VERIFYING => VerificationSession::from_bytes(&Solana, &proof_buffer.session)
+                .ok_or(OptimizationLogError::InvalidProof)?,
+            _ => return Ok(()), // Already verified
+        };
+
+        let verifier = UltraHonkVerifier::new(&Solana, &noir_vk.vk)
+            .map_err(|_| OptimizationLogError::InvalidVerificationKey)?;
+        let step = verifier
+            .advance_session(
+                &Solana,
+                &mut session,
+                &proof_buffer.proof,
+                &proof_buffer.public_input,
+                max_rounds,
+            )
+            .map_err(|e| match e {
+                VerifyError::SessionMismatch => OptimizationLogError::ProofIncomplete,
+                _ => OptimizationLogError::InvalidProof,
+            })?;
+
+        proof_buffer.session.copy_from_slice(&session.to_bytes());
+        if step != SessionStep::Done {
+            proof_buffer.status = PROOF_BUFFER_VERIFYING;
+            return Ok(());
+        }
+
+        proof_buffer.status = PROOF_BUFFER_VERIFIED;
+
+        emit!(NoirProofVerified {
+            proof_buffer: ctx.accounts.proof_buffer.key(),
+            owner: proof_buffer.owner,
+            noir_circuit_id: proof_buffer.noir_circuit_id,
+            public_input: proof_buffer.public_input,
+            proof_hash: hash(&proof_buffer.proof),
+        });
+
+        Ok(())
+    }
+
+    /// Close an unused or rejected proof buffer and reclaim its rent
+    pub fn close_proof_buffer(_ctx: Context<CloseProofBuffer>) -> Result<()> {
+        Ok(())
+    }
 }
 
 // ============= ACCOUNTS =============
@@ -584,6 +804,28 @@ pub struct AccessRequest {
     pub encryption_scheme: u8,              // Encryption scheme used
 }
 
+#[account]
+pub struct NoirVerificationKey {
+    pub noir_circuit_id: [u8; 4],
+    pub vk: Vec<u8>,                       // bb `write_vk` output
+    pub is_finalized: bool,                // Parsed and locked
+    pub bump: u8,
+}
+
+/// A Noir proof uploaded in chunks and verified across instructions
+#[account(zero_copy)]
+pub struct ProofBuffer {
+    pub owner: Pubkey,                     // Only signer that can write, verify or close
+    pub expires_at: u64,                   // Last slot `verify_proof_buffer` accepts
+    pub noir_circuit_id: [u8; 4],
+    pub written: u32,                      // Proof bytes uploaded so far
+    pub public_input: [u8; 32],            // The circuit's one public input
+    pub status: u8,                        // PROOF_BUFFER_* below
+    pub _padding: [u8; 5],
+    pub session: [u8; SESSION_BYTES],      // Verifier state between steps
+    pub proof: [u8; PROOF_BYTES],
+}
+
 #[account]
 pub struct GovernanceConfig {
     pub governance_authority: Pubkey,      // Multi-sig PDA
@@ -637,6 +879,8 @@ pub struct SubmitOptimizationLog<'info> {
         bump
     )]
     pub optimization_log: Account<'info, OptimizationLog>,
+    #[account(mut, close = submitter)]
+    pub encryption_proof: AccountLoader<'info, ProofBuffer>,
     #[account(mut)]
     pub submitter: Signer<'info>,
     pub system_program: Program<'info, System>,
@@ -654,6 +898,8 @@ pub struct ValidateWithProof<'info> {
     pub validator_stake: Account<'info, ValidatorStake>,
     #[account(mut)]
     pub validator_reputation: Account<'info, ValidatorReputation>,
+    #[account(mut, close = validator)]
+    pub proof_buffer: AccountLoader<'info, ProofBuffer>,
     #[account(mut)]
     pub validator: Signer<'info>,
     #[account(mut)]
@@ -740,6 +986,85 @@ pub struct LinkAttentionToken<'info> {
     pub submitter: Signer<'info>,
 }
 
+#[derive(Accounts)]
+#[instruction(noir_circuit_id: [u8; 4])]
+pub struct InitNoirVk<'info> {
+    #[account(
+        init,
+        payer = governance_authority,
+        space = 8 + 4 + (4 + NOIR_VK_BYTES) + 1 + 1,
+        seeds = [b"noir_vk", noir_circuit_id.as_ref()],
+        bump
+    )]
+    pub noir_vk: Account<'info, NoirVerificationKey>,
+    pub governance_config: Account<'info, GovernanceConfig>,
+    #[account(
+        mut,
+        constraint = governance_authority.key() == governance_config.governance_authority
+    )]
+    pub governance_authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WriteNoirVk<'info> {
+    #[account(
+        mut,
+        seeds = [b"noir_vk", noir_vk.noir_circuit_id.as_ref()],
+        bump = noir_vk.bump
+    )]
+    pub noir_vk: Account<'info, NoirVerificationKey>,
+    pub governance_config: Account<'info, GovernanceConfig>,
+    #[account(
+        constraint = governance_authority.key() == governance_config.governance_authority
+    )]
+    pub governance_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitProofBuffer<'info> {
+    #[account(zero)]
+    pub proof_buffer: AccountLoader<'info, ProofBuffer>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UseProofBuffer<'info> {
+    #[account(
+        mut,
+        constraint = proof_buffer.load()?.owner == owner.key() @ OptimizationLogError::UnauthorizedProofBuffer
+    )]
+    pub proof_buffer: AccountLoader<'info, ProofBuffer>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyProofBuffer<'info> {
+    #[account(
+        mut,
+        constraint = proof_buffer.load()?.owner == owner.key() @ OptimizationLogError::UnauthorizedProofBuffer
+    )]
+    pub proof_buffer: AccountLoader<'info, ProofBuffer>,
+    #[account(
+        seeds = [b"noir_vk", noir_vk.noir_circuit_id.as_ref()],
+        bump = noir_vk.bump
+    )]
+    pub noir_vk: Account<'info, NoirVerificationKey>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseProofBuffer<'info> {
+    #[account(
+        mut,
+        close = owner,
+        constraint = proof_buffer.load()?.owner == owner.key() @ OptimizationLogError::UnauthorizedProofBuffer
+    )]
+    pub proof_buffer: AccountLoader<'info, ProofBuffer>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
 // ============= EVENTS =============
 
 #[event]
@@ -750,6 +1075,7 @@ pub struct OptimizationLogSubmitted {
     pub created_at: i64,
     pub light_proof_hash: [u8; 32],
     pub compression_ratio: u16,
+    pub encryption_proof_hash: [u8; 32],
 }
 
 #[event]
@@ -836,6 +1162,21 @@ pub struct AttentionTokenLinked {
     pub created_at: i64,
 }
 
+#[event]
+pub struct NoirVkRegistered {
+    pub noir_circuit_id: [u8; 4],
+    pub vk_hash: [u8; 32],
+}
+
+#[event]
+pub struct NoirProofVerified {
+    pub proof_buffer: Pubkey,
+    pub owner: Pubkey,
+    pub noir_circuit_id: [u8; 4],
+    pub public_input: [u8; 32],
+    pub proof_hash: [u8; 32],
+}
+
 // ============= ERRORS =============
 
 #[error_code]
@@ -898,6 +1239,26 @@ pub enum OptimizationLogError {
     InsufficientValidatorsForToken,
     #[msg("Attention token already exists for this case study")]
     AttentionTokenAlreadyExists,
+    #[msg("No finalized verification key for this Noir circuit")]
+    NoirVkNotRegistered,
+    #[msg("Verification key already finalized")]
+    NoirVkFinalized,
+    #[msg("Invalid UltraHonk verification key")]
+    InvalidVerificationKey,
+    #[msg("Chunk does not continue the upload or overflows it")]
+    ChunkOutOfOrder,
+    #[msg("Proof buffer no longer accepts chunks")]
+    ProofBufferNotWritable,
+    #[msg("Proof upload or verification incomplete")]
+    ProofIncomplete,
+    #[msg("Proof has not been verified")]
+    ProofNotVerified,
+    #[msg("Proof public input does not match")]
+    PublicInputMismatch,
+    #[msg("Signer does not own this proof buffer")]
+    UnauthorizedProofBuffer,
+    #[msg("Proof buffer expired; close it and upload again")]
+    ProofBufferExpired,
 }
 
 // ============= CONSTANTS =============
@@ -907,6 +1268,19 @@ pub enum OptimizationLogError {
 pub const MINIMUM_VALIDATOR_STAKE: u64 = 100_000_000; // 100 DBC (enforced by treasury)
 pub const CRITICAL_RISK_THRESHOLD: u8 = 75;
 
+// Noir circuits verified on-chain (UltraHonk, bb v0.87.0, Keccak transcript)
+pub const VALIDATION_CIRCUIT_ID: [u8; 4] = *b"noir";
+pub const ENCRYPTION_CIRCUIT_ID: [u8; 4] = *b"encr";  // Public input: metadata_hash
+pub const NOIR_VK_BYTES: usize = BbVersion::V0_87_0.layout().vk_bytes;
+pub const PROOF_BUFFER_SPACE: usize = 8 + std::mem::size_of::<ProofBuffer>();
+
+pub const PROOF_BUFFER_TTL_SLOTS: u64 = 9_000;  // ~1 hour to upload and verify
+
+// ProofBuffer.status
+pub const PROOF_BUFFER_WRITING: u8 = 0;
+pub const PROOF_BUFFER_VERIFYING: u8 = 1;
+pub const PROOF_BUFFER_VERIFIED: u8 = 2;
+
 // ============= HELPER FUNCTIONS =============
 
 fn calculate_validator_weight(reputation: &Account<ValidatorReputation>) -> Result<u32> {
@@ -930,4 +1304,7 @@ fn calculate_validator_weight(reputation: &Account<ValidatorReputation>) -> Resu
 fn hash(data: &[u8]) -> [u8; 32] {
     use solana_program::keccak;
     keccak::hash(data).to_bytes()
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,277 @@
+//! Instruction tests.
+//!
+//! Each instruction goes through the program's `entry` on accounts
+//! serialized the way the runtime passes them, so the account constraints,
+//! the handler and `close` all run as they do on-chain. A failed
+//! instruction leaves the accounts as they were, and the clock is fixed at
+//! `SLOT`.
+//!
+//! Anchor cannot make CPIs off-chain, so an instruction with an `init`
+//! account or a token transfer has its handler called on a hand-built
+//! `Context` instead ([`Bank::run`]), with the `init` account already
+//! allocated, and is only taken as far as the CPI.
+
+use crate::{ProofBuffer, ID, PROOF_BUFFER_SPACE, PROOF_BUFFER_VERIFIED};
+use anchor_lang::{
+    prelude::*, system_program, AccountDeserialize, AccountSerialize, Discriminator,
+    InstructionData, ToAccountMetas,
+};
+use solana_program::{
+    entrypoint::{
+        deserialize, ProgramResult, MAX_PERMITTED_DATA_INCREASE, NON_DUP_MARKER, SUCCESS,
+    },
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+};
+use std::{collections::HashMap, sync::Once};
+
+mod submit;
+mod upload;
+mod validate;
+mod verify;
+
+/// The browser prover's VK and proof, from `api/stellar/` (bb v0.87.0,
+/// public inputs `[1, 20]`).
+const VK: &[u8] = include_bytes!("../../../../api/stellar/vk.bin");
+const PROOF: &[u8] = include_bytes!("../../../../api/stellar/proof.bin");
+
+/// Slot and unix time the clock reads.
+pub const SLOT: u64 = 1_000;
+pub const NOW: i64 = 1_700_000_000;
+
+struct Stubs;
+
+impl SyscallStubs for Stubs {
+    fn sol_log(&self, _message: &str) {}
+
+    fn sol_log_data(&self, _fields: &[&[u8]]) {}
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let clock = Clock {
+            slot: SLOT,
+            unix_timestamp: NOW,
+            ..Clock::default()
+        };
+        // SAFETY: the caller passes a `Clock` to write.
+        unsafe { (var_addr as *mut Clock).write(clock) };
+        SUCCESS
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        // SAFETY: the caller passes a `Rent` to write.
+        unsafe { (var_addr as *mut Rent).write(Rent::default()) };
+        SUCCESS
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccountState {
+    pub owner: Pubkey,
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub executable: bool,
+}
+
+/// Accounts by key, as the program last left them.
+pub struct Bank(HashMap<Pubkey, AccountState>);
+
+impl Bank {
+    /// The system and token programs, plus whatever the test adds.
+    pub fn new() -> Self {
+        static STUBS: Once = Once::new();
+        STUBS.call_once(|| {
+            set_syscall_stubs(Box::new(Stubs));
+        });
+
+        let mut bank = Bank(HashMap::new());
+        for program in [system_program::ID, anchor_spl::token::ID] {
+            bank.0.insert(
+                program,
+                AccountState {
+                    owner: Pubkey::default(),
+                    lamports: 1,
+                    data: Vec::new(),
+                    executable: true,
+                },
+            );
+        }
+        bank
+    }
+
+    /// A wallet with enough lamports to pay for any account here.
+    pub fn wallet(&mut self) -> Pubkey {
+        let key = Pubkey::new_unique();
+        self.0.insert(
+            key,
+            AccountState {
+                owner: system_program::ID,
+                lamports: 1_000_000_000_000,
+                data: Vec::new(),
+                executable: false,
+            },
+        );
+        key
+    }
+
+    /// `data` owned by `owner`, rent exempt.
+    pub fn put(&mut self, key: Pubkey, owner: Pubkey, data: Vec<u8>) {
+        let lamports = Rent::default().minimum_balance(data.len());
+        self.0.insert(
+            key,
+            AccountState {
+                owner,
+                lamports,
+                data,
+                executable: false,
+            },
+        );
+    }
+
+    /// A program account holding `value`.
+    pub fn put_account<T: AccountSerialize>(&mut self, key: Pubkey, value: &T) {
+        let mut data = Vec::new();
+        value.try_serialize(&mut data).unwrap();
+        self.put(key, ID, data);
+    }
+
+    /// An uninitialized program account of `space` bytes, as a client
+    /// creates for a `zero` constraint and an `init` one allocates.
+    pub fn put_empty(&mut self, key: Pubkey, space: usize) {
+        self.put(key, ID, vec![0; space]);
+    }
+
+    /// A program account of `space` bytes holding `value`, as its `init`
+    /// instruction leaves it.
+    pub fn put_init<T: AccountSerialize>(&mut self, key: Pubkey, space: usize, value: &T) {
+        let mut data = vec![0; space];
+        value.try_serialize(&mut &mut data[..]).unwrap();
+        self.put(key, ID, data);
+    }
+
+    pub fn put_proof_buffer(&mut self, key: Pubkey, buffer: &ProofBuffer) {
+        let mut data = ProofBuffer::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(buffer));
+        assert_eq!(data.len(), PROOF_BUFFER_SPACE);
+        self.put(key, ID, data);
+    }
+
+    pub fn state(&self, key: &Pubkey) -> &AccountState {
+        &self.0[key]
+    }
+
+    pub fn account<T: AccountDeserialize>(&self, key: &Pubkey) -> T {
+        T::try_deserialize(&mut &self.0[key].data[..]).unwrap()
+    }
+
+    pub fn proof_buffer(&self, key: &Pubkey) -> ProofBuffer {
+        let data = &self.0[key].data;
+        assert_eq!(&data[..8], ProofBuffer::DISCRIMINATOR);
+        bytemuck::pod_read_unaligned(&data[8..])
+    }
+
+    /// Run one instruction. The accounts are only updated if it succeeds.
+    pub fn process(
+        &mut self,
+        accounts: impl ToAccountMetas,
+        ix: impl InstructionData,
+    ) -> std::result::Result<(), ProgramError> {
+        self.run(accounts, |program_id, infos| {
+            crate::entry(program_id, infos, &ix.data())
+        })
+    }
+
+    /// Run `handler` on the accounts as the runtime would pass them to the
+    /// program. The accounts are only updated if it succeeds.
+    pub fn run(
+        &mut self,
+        accounts: impl ToAccountMetas,
+        handler: impl for<'a> FnOnce(&'a Pubkey, &'a [AccountInfo<'a>]) -> ProgramResult,
+    ) -> std::result::Result<(), ProgramError> {
+        let metas = accounts.to_account_metas(None);
+        let mut input = self.serialize(&metas);
+        // SAFETY: `input` is laid out as the runtime lays out a program's
+        // input, and outlives `infos`.
+        let (program_id, infos, _) = unsafe { deserialize(input.as_mut_ptr() as *mut u8) };
+        handler(program_id, &infos)?;
+
+        for (i, (meta, info)) in metas.iter().zip(&infos).enumerate() {
+            if metas[..i].iter().any(|m| m.pubkey == meta.pubkey) {
+                continue;
+            }
+            self.0.insert(
+                meta.pubkey,
+                AccountState {
+                    owner: *info.owner,
+                    lamports: info.lamports(),
+                    data: info.data.borrow().to_vec(),
+                    executable: info.executable,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    /// The runtime's input layout (`solana_program::entrypoint::deserialize`),
+    /// in a `u64` buffer for its alignment.
+    fn serialize(&self, metas: &[AccountMeta]) -> Vec<u64> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&(metas.len() as u64).to_le_bytes());
+        for (i, meta) in metas.iter().enumerate() {
+            if let Some(first) = metas[..i].iter().position(|m| m.pubkey == meta.pubkey) {
+                out.push(first as u8);
+                out.extend_from_slice(&[0; 7]);
+                continue;
+            }
+            let state = self.0.get(&meta.pubkey).cloned().unwrap_or(AccountState {
+                owner: system_program::ID,
+                lamports: 0,
+                data: Vec::new(),
+                executable: false,
+            });
+            out.push(NON_DUP_MARKER);
+            out.push(meta.is_signer as u8);
+            out.push(meta.is_writable as u8);
+            out.push(state.executable as u8);
+            out.extend_from_slice(&[0; 4]);
+            out.extend_from_slice(meta.pubkey.as_ref());
+            out.extend_from_slice(state.owner.as_ref());
+            out.extend_from_slice(&state.lamports.to_le_bytes());
+            out.extend_from_slice(&(state.data.len() as u64).to_le_bytes());
+            out.extend_from_slice(&state.data);
+            out.resize(out.len() + MAX_PERMITTED_DATA_INCREASE, 0);
+            out.resize(out.len().next_multiple_of(8), 0);
+            out.extend_from_slice(&u64::MAX.to_le_bytes());
+        }
+        // No instruction data: `run` passes it to the handler itself.
+        out.extend_from_slice(&0u64.to_le_bytes());
+        out.extend_from_slice(ID.as_ref());
+
+        let mut words = vec![0u64; out.len().div_ceil(8)];
+        bytemuck::cast_slice_mut::<u64, u8>(&mut words)[..out.len()].copy_from_slice(&out);
+        words
+    }
+}
+
+/// The error `entry` returns for `e`.
+pub fn err(e: impl Into<anchor_lang::error::Error>) -> ProgramError {
+    e.into().into()
+}
+
+/// A verified proof buffer of `owner` for `noir_circuit_id`, with the
+/// `api/stellar/` proof.
+pub fn verified_buffer(
+    owner: Pubkey,
+    noir_circuit_id: [u8; 4],
+    public_input: [u8; 32],
+) -> ProofBuffer {
+    let mut buffer = ProofBuffer {
+        owner,
+        expires_at: SLOT,
+        noir_circuit_id,
+        written: PROOF.len() as u32,
+        public_input,
+        status: PROOF_BUFFER_VERIFIED,
+        ..bytemuck::Zeroable::zeroed()
+    };
+    buffer.proof.copy_from_slice(PROOF);
+    buffer
+}
@@ -0,0 +1,146 @@
+use super::{err, verified_buffer, Bank, NOW};
+use crate::{
+    accounts, optimization_log, OptimizationLog, OptimizationLogError, SubmitOptimizationLog,
+    SubmitOptimizationLogBumps, ValidationStatus, ENCRYPTION_CIRCUIT_ID, ID,
+    PROOF_BUFFER_VERIFYING, VALIDATION_CIRCUIT_ID,
+};
+use anchor_lang::{prelude::*, system_program, AccountsExit};
+
+const METADATA_HASH: [u8; 32] = [0x5a; 32];
+const NONCE: i64 = 7;
+
+struct Submission {
+    optimization_log: Pubkey,
+    bump: u8,
+    encryption_proof: Pubkey,
+    submitter: Pubkey,
+}
+
+/// A submitter holding a verified encryption proof of `METADATA_HASH`.
+fn setup(bank: &mut Bank) -> Submission {
+    let submitter = bank.wallet();
+    let encryption_proof = Pubkey::new_unique();
+    bank.put_proof_buffer(
+        encryption_proof,
+        &verified_buffer(submitter, ENCRYPTION_CIRCUIT_ID, METADATA_HASH),
+    );
+    let (optimization_log, bump) = Pubkey::find_program_address(
+        &[
+            b"optimization_log",
+            submitter.as_ref(),
+            &NONCE.to_le_bytes(),
+        ],
+        &ID,
+    );
+    // As `SubmitOptimizationLog` allocates it.
+    bank.put_empty(
+        optimization_log,
+        8 + 32
+            + 32
+            + (4 + 46)
+            + 32
+            + 1
+            + 2
+            + 8
+            + 1
+            + 4
+            + 4
+            + 1
+            + 1
+            + 1
+            + 1
+            + 32
+            + 2
+            + (1 + 32)
+            + (1 + 8),
+    );
+    Submission {
+        optimization_log,
+        bump,
+        encryption_proof,
+        submitter,
+    }
+}
+
+/// `submit_encrypted_optimization_log`.
+fn submit(bank: &mut Bank, s: &Submission) -> std::result::Result<(), ProgramError> {
+    let metas = accounts::SubmitOptimizationLog {
+        optimization_log: s.optimization_log,
+        encryption_proof: s.encryption_proof,
+        submitter: s.submitter,
+        system_program: system_program::ID,
+    };
+    let bump = s.bump;
+    bank.run(metas, |program_id, infos| {
+        let mut accounts = SubmitOptimizationLog {
+            optimization_log: Account::try_from_unchecked(&infos[0])?,
+            encryption_proof: AccountLoader::try_from(&infos[1])?,
+            submitter: Signer::try_from(&infos[2])?,
+            system_program: Program::try_from(&infos[3])?,
+        };
+        optimization_log::submit_encrypted_optimization_log(
+            Context::new(
+                program_id,
+                &mut accounts,
+                &[],
+                SubmitOptimizationLogBumps {
+                    optimization_log: bump,
+                },
+            ),
+            NONCE,
+            format!("Qm{}", "b".repeat(44)),
+            METADATA_HASH,
+            1,
+            30,
+            vec![1, 2, 3],
+            10,
+        )?;
+        accounts.exit(program_id)?;
+        Ok(())
+    })
+}
+
+#[test]
+fn a_verified_encryption_proof_records_the_log() {
+    let mut bank = Bank::new();
+    let s = setup(&mut bank);
+    submit(&mut bank, &s).unwrap();
+
+    let log: OptimizationLog = bank.account(&s.optimization_log);
+    assert_eq!(log.submitter, s.submitter);
+    assert_eq!(log.metadata_hash, METADATA_HASH);
+    assert_eq!(log.optimization_category, 1);
+    assert_eq!(log.created_at, NOW);
+    assert_eq!(log.bump, s.bump);
+    assert!(log.validation_status == ValidationStatus::Pending);
+
+    // The proof is spent: its buffer is closed to the submitter.
+    let closed = bank.state(&s.encryption_proof);
+    assert_eq!((closed.lamports, closed.data.len()), (0, 0));
+}
+
+#[test]
+fn the_proof_must_be_the_submitters_verified_encryption_proof() {
+    let mut bank = Bank::new();
+    let s = setup(&mut bank);
+    let good = bank.proof_buffer(&s.encryption_proof);
+
+    let mut other_owner = good;
+    other_owner.owner = Pubkey::new_unique();
+    let mut other_circuit = good;
+    other_circuit.noir_circuit_id = VALIDATION_CIRCUIT_ID;
+    let mut unverified = good;
+    unverified.status = PROOF_BUFFER_VERIFYING;
+    let mut other_hash = good;
+    other_hash.public_input = [0x5b; 32];
+
+    for (buffer, error) in [
+        (other_owner, OptimizationLogError::UnauthorizedProofBuffer),
+        (other_circuit, OptimizationLogError::InvalidCircuitId),
+        (unverified, OptimizationLogError::MissingEncryptionProof),
+        (other_hash, OptimizationLogError::PublicInputMismatch),
+    ] {
+        bank.put_proof_buffer(s.encryption_proof, &buffer);
+        assert_eq!(submit(&mut bank, &s), Err(err(error)));
+    }
+}
@@ -0,0 +1,268 @@
+use super::{err, Bank, PROOF, SLOT, VK};
+use crate::{
+    accounts, instruction, GovernanceConfig, NoirVerificationKey, OptimizationLogError, ID,
+    NOIR_VK_BYTES, PROOF_BUFFER_SPACE, PROOF_BUFFER_TTL_SLOTS, PROOF_BUFFER_VERIFYING,
+    PROOF_BUFFER_WRITING, VALIDATION_CIRCUIT_ID,
+};
+use anchor_lang::{error::ErrorCode, prelude::*};
+
+/// Bytes per `write_*_chunk` instruction.
+const CHUNK: usize = 900;
+
+pub struct Governance {
+    pub config: Pubkey,
+    pub authority: Pubkey,
+}
+
+pub fn governance(bank: &mut Bank) -> Governance {
+    let config = Pubkey::new_unique();
+    let authority = bank.wallet();
+    let value = GovernanceConfig {
+        governance_authority: authority,
+        min_validators_for_approval: 5,
+        slash_appeal_period_days: 7,
+        critical_risk_threshold: 75,
+    };
+    bank.put_account(config, &value);
+    Governance { config, authority }
+}
+
+/// The `api/stellar/` VK declared with one public input instead of two,
+/// the shape `finalize_noir_vk` accepts. Proofs against it get through the
+/// transcript and the first sumcheck round, then fail.
+pub fn one_input_vk() -> Vec<u8> {
+    let mut vk = VK.to_vec();
+    vk[16..24].copy_from_slice(&17u64.to_be_bytes());
+    vk
+}
+
+fn write_vk_accounts(gov: &Governance, noir_vk: Pubkey) -> accounts::WriteNoirVk {
+    accounts::WriteNoirVk {
+        noir_vk,
+        governance_config: gov.config,
+        governance_authority: gov.authority,
+    }
+}
+
+/// A VK account for `noir_circuit_id` as `init_noir_vk` leaves it, with
+/// `vk` uploaded in chunks.
+pub fn write_vk(bank: &mut Bank, gov: &Governance, noir_circuit_id: [u8; 4], vk: &[u8]) -> Pubkey {
+    let (noir_vk, bump) = Pubkey::find_program_address(&[b"noir_vk", &noir_circuit_id], &ID);
+    bank.put_init(
+        noir_vk,
+        8 + 4 + (4 + NOIR_VK_BYTES) + 1 + 1,
+        &NoirVerificationKey {
+            noir_circuit_id,
+            vk: Vec::new(),
+            is_finalized: false,
+            bump,
+        },
+    );
+    for (i, chunk) in vk.chunks(CHUNK).enumerate() {
+        bank.process(
+            write_vk_accounts(gov, noir_vk),
+            instruction::WriteNoirVk {
+                offset: (i * CHUNK) as u32,
+                chunk: chunk.to_vec(),
+            },
+        )
+        .unwrap();
+    }
+    noir_vk
+}
+
+pub fn finalize_vk(
+    bank: &mut Bank,
+    gov: &Governance,
+    noir_vk: Pubkey,
+) -> std::result::Result<(), ProgramError> {
+    bank.process(
+        write_vk_accounts(gov, noir_vk),
+        instruction::FinalizeNoirVk {},
+    )
+}
+
+/// Register [`one_input_vk`] for `noir_circuit_id`.
+pub fn register_vk(bank: &mut Bank, noir_circuit_id: [u8; 4]) -> Pubkey {
+    let gov = governance(bank);
+    let noir_vk = write_vk(bank, &gov, noir_circuit_id, &one_input_vk());
+    finalize_vk(bank, &gov, noir_vk).unwrap();
+    noir_vk
+}
+
+pub fn write_chunk(
+    bank: &mut Bank,
+    proof_buffer: Pubkey,
+    owner: Pubkey,
+    offset: usize,
+    chunk: &[u8],
+) -> std::result::Result<(), ProgramError> {
+    bank.process(
+        accounts::UseProofBuffer {
+            proof_buffer,
+            owner,
+        },
+        instruction::WriteProofChunk {
+            offset: offset as u32,
+            chunk: chunk.to_vec(),
+        },
+    )
+}
+
+/// A proof buffer of `owner` with `proof` uploaded in chunks.
+pub fn upload_proof(
+    bank: &mut Bank,
+    owner: Pubkey,
+    noir_circuit_id: [u8; 4],
+    public_input: [u8; 32],
+    proof: &[u8],
+) -> Pubkey {
+    let proof_buffer = Pubkey::new_unique();
+    bank.put_empty(proof_buffer, PROOF_BUFFER_SPACE);
+    bank.process(
+        accounts::InitProofBuffer {
+            proof_buffer,
+            owner,
+        },
+        instruction::InitProofBuffer {
+            noir_circuit_id,
+            public_input,
+        },
+    )
+    .unwrap();
+    for (i, chunk) in proof.chunks(CHUNK).enumerate() {
+        write_chunk(bank, proof_buffer, owner, i * CHUNK, chunk).unwrap();
+    }
+    proof_buffer
+}
+
+#[test]
+fn vk_is_uploaded_in_chunks_and_finalized() {
+    let mut bank = Bank::new();
+    let gov = governance(&mut bank);
+    let vk = one_input_vk();
+    let noir_vk = write_vk(&mut bank, &gov, VALIDATION_CIRCUIT_ID, &vk[..CHUNK]);
+
+    // Chunks must continue the upload and stay within a VK.
+    let write = |bank: &mut Bank, offset: usize, chunk: &[u8]| {
+        bank.process(
+            write_vk_accounts(&gov, noir_vk),
+            instruction::WriteNoirVk {
+                offset: offset as u32,
+                chunk: chunk.to_vec(),
+            },
+        )
+    };
+    assert_eq!(
+        write(&mut bank, 0, &vk[CHUNK..]),
+        Err(err(OptimizationLogError::ChunkOutOfOrder))
+    );
+    assert_eq!(
+        write(&mut bank, CHUNK, &[0; NOIR_VK_BYTES]),
+        Err(err(OptimizationLogError::ChunkOutOfOrder))
+    );
+    // Only the governance authority writes.
+    let stranger = Governance {
+        config: gov.config,
+        authority: bank.wallet(),
+    };
+    assert_eq!(
+        bank.process(
+            write_vk_accounts(&stranger, noir_vk),
+            instruction::WriteNoirVk {
+                offset: CHUNK as u32,
+                chunk: vk[CHUNK..].to_vec(),
+            },
+        ),
+        Err(err(ErrorCode::ConstraintRaw))
+    );
+    write(&mut bank, CHUNK, &vk[CHUNK..]).unwrap();
+
+    finalize_vk(&mut bank, &gov, noir_vk).unwrap();
+    let stored: NoirVerificationKey = bank.account(&noir_vk);
+    assert_eq!(stored.noir_circuit_id, VALIDATION_CIRCUIT_ID);
+    assert_eq!(stored.vk, vk);
+    assert!(stored.is_finalized);
+
+    assert_eq!(
+        write(&mut bank, vk.len(), &[0]),
+        Err(err(OptimizationLogError::NoirVkFinalized))
+    );
+    assert_eq!(
+        finalize_vk(&mut bank, &gov, noir_vk),
+        Err(err(OptimizationLogError::NoirVkFinalized))
+    );
+}
+
+#[test]
+fn vk_must_take_one_public_input() {
+    let mut bank = Bank::new();
+    let gov = governance(&mut bank);
+    let noir_vk = write_vk(&mut bank, &gov, VALIDATION_CIRCUIT_ID, VK);
+    assert_eq!(
+        finalize_vk(&mut bank, &gov, noir_vk),
+        Err(err(OptimizationLogError::InvalidVerificationKey))
+    );
+    assert!(!bank.account::<NoirVerificationKey>(&noir_vk).is_finalized);
+}
+
+#[test]
+fn proof_is_uploaded_in_chunks() {
+    let mut bank = Bank::new();
+    let owner = bank.wallet();
+    let public_input = [7; 32];
+    let proof_buffer = upload_proof(
+        &mut bank,
+        owner,
+        VALIDATION_CIRCUIT_ID,
+        public_input,
+        &PROOF[..CHUNK],
+    );
+
+    let buffer = bank.proof_buffer(&proof_buffer);
+    assert_eq!(buffer.owner, owner);
+    assert_eq!(buffer.expires_at, SLOT + PROOF_BUFFER_TTL_SLOTS);
+    assert_eq!(buffer.noir_circuit_id, VALIDATION_CIRCUIT_ID);
+    assert_eq!(buffer.public_input, public_input);
+    assert_eq!(buffer.status, PROOF_BUFFER_WRITING);
+    assert_eq!(buffer.written, CHUNK as u32);
+
+    // Chunks must continue the upload and stay within a proof.
+    assert_eq!(
+        write_chunk(&mut bank, proof_buffer, owner, 0, &PROOF[CHUNK..2 * CHUNK]),
+        Err(err(OptimizationLogError::ChunkOutOfOrder))
+    );
+    assert_eq!(
+        write_chunk(&mut bank, proof_buffer, owner, CHUNK, PROOF),
+        Err(err(OptimizationLogError::ChunkOutOfOrder))
+    );
+    // Only the owner writes.
+    let stranger = bank.wallet();
+    assert_eq!(
+        write_chunk(&mut bank, proof_buffer, stranger, CHUNK, &PROOF[CHUNK..]),
+        Err(err(OptimizationLogError::UnauthorizedProofBuffer))
+    );
+
+    for (i, chunk) in PROOF.chunks(CHUNK).enumerate().skip(1) {
+        write_chunk(&mut bank, proof_buffer, owner, i * CHUNK, chunk).unwrap();
+    }
+    let buffer = bank.proof_buffer(&proof_buffer);
+    assert_eq!(buffer.written as usize, PROOF.len());
+    assert_eq!(&buffer.proof[..], PROOF);
+
+    // Once verification starts the upload is locked.
+    let mut locked = buffer;
+    locked.written = CHUNK as u32;
+    locked.status = PROOF_BUFFER_VERIFYING;
+    bank.put_proof_buffer(proof_buffer, &locked);
+    assert_eq!(
+        write_chunk(
+            &mut bank,
+            proof_buffer,
+            owner,
+            CHUNK,
+            &PROOF[CHUNK..2 * CHUNK]
+        ),
+        Err(err(OptimizationLogError::ProofBufferNotWritable))
+    );
+}
@@ -0,0 +1,177 @@
+use super::{err, verified_buffer, Bank, NOW};
+use crate::{
+    accounts, optimization_log, OptimizationLog, OptimizationLogError, ValidateWithProof,
+    ValidationStatus, ValidationType, ValidatorReputation, ValidatorTier, ENCRYPTION_CIRCUIT_ID,
+    MINIMUM_VALIDATOR_STAKE, PROOF_BUFFER_VERIFYING, VALIDATION_CIRCUIT_ID,
+};
+use anchor_lang::{prelude::*, system_program, AccountsExit};
+use anchor_spl::token::spl_token;
+use solana_program::program_pack::Pack;
+
+const PUBLIC_INPUTS: [u8; 32] = [0x42; 32];
+const CIRCUIT_PARAMS_HASH: [u8; 32] = [0x33; 32];
+
+struct Validation {
+    optimization_log: Pubkey,
+    validator_stake: Pubkey,
+    validator_reputation: Pubkey,
+    proof_buffer: Pubkey,
+    validator: Pubkey,
+    validator_token_account: Pubkey,
+    stake_escrow: Pubkey,
+}
+
+fn token_account(bank: &mut Bank, owner: Pubkey, amount: u64) -> Pubkey {
+    let key = Pubkey::new_unique();
+    let mut data = vec![0; spl_token::state::Account::LEN];
+    spl_token::state::Account {
+        mint: Pubkey::new_unique(),
+        owner,
+        amount,
+        state: spl_token::state::AccountState::Initialized,
+        ..Default::default()
+    }
+    .pack_into_slice(&mut data);
+    bank.put(key, spl_token::ID, data);
+    key
+}
+
+/// A pending log, and a Silver validator holding a verified proof for it.
+fn setup(bank: &mut Bank) -> Validation {
+    let validator = bank.wallet();
+    let optimization_log = Pubkey::new_unique();
+    bank.put_account(
+        optimization_log,
+        &OptimizationLog {
+            ephemeral_id: Pubkey::new_unique(),
+            submitter: Pubkey::new_unique(),
+            ipfs_cid: format!("Qm{}", "a".repeat(44)),
+            metadata_hash: [1; 32],
+            optimization_category: 0,
+            execution_duration: 30,
+            created_at: NOW,
+            validation_status: ValidationStatus::Pending,
+            approval_count: 0,
+            rejection_count: 0,
+            reputation_score: 0,
+            is_paused: false,
+            threshold_shares_required: 3,
+            light_proof_hash: [2; 32],
+            compression_ratio: 10,
+            attention_token_mint: None,
+            attention_token_created_at: None,
+            bump: 255,
+        },
+    );
+    let validator_reputation = Pubkey::new_unique();
+    bank.put_account(
+        validator_reputation,
+        &ValidatorReputation {
+            validator,
+            total_validations: 0,
+            accurate_validations: 0,
+            total_slashes: 0,
+            reputation_score: 50,
+            tier: ValidatorTier::Silver,
+            pending_slash: None,
+        },
+    );
+    let proof_buffer = Pubkey::new_unique();
+    bank.put_proof_buffer(
+        proof_buffer,
+        &verified_buffer(validator, VALIDATION_CIRCUIT_ID, PUBLIC_INPUTS),
+    );
+    // As `ValidateWithProof` allocates it.
+    let validator_stake = Pubkey::new_unique();
+    bank.put_empty(
+        validator_stake,
+        8 + 32 + 32 + 8 + 1 + 32 + 32 + 4 + 32 + 32 + 8 + 1 + 8 + 4,
+    );
+    Validation {
+        optimization_log,
+        validator_stake,
+        validator_reputation,
+        proof_buffer,
+        validator,
+        validator_token_account: token_account(bank, validator, MINIMUM_VALIDATOR_STAKE),
+        stake_escrow: token_account(bank, Pubkey::new_unique(), 0),
+    }
+}
+
+/// `validator_prove_integrity`, as far as its stake transfer.
+fn validate(
+    bank: &mut Bank,
+    v: &Validation,
+    noir_circuit_id: [u8; 4],
+) -> std::result::Result<(), ProgramError> {
+    let metas = accounts::ValidateWithProof {
+        optimization_log: v.optimization_log,
+        validator_stake: v.validator_stake,
+        validator_reputation: v.validator_reputation,
+        proof_buffer: v.proof_buffer,
+        validator: v.validator,
+        validator_token_account: v.validator_token_account,
+        stake_escrow: v.stake_escrow,
+        token_program: spl_token::ID,
+        system_program: system_program::ID,
+    };
+    bank.run(metas, |program_id, infos| {
+        let mut accounts = ValidateWithProof {
+            optimization_log: Account::try_from(&infos[0])?,
+            validator_stake: Account::try_from_unchecked(&infos[1])?,
+            validator_reputation: Account::try_from(&infos[2])?,
+            proof_buffer: AccountLoader::try_from(&infos[3])?,
+            validator: Signer::try_from(&infos[4])?,
+            validator_token_account: Account::try_from(&infos[5])?,
+            stake_escrow: Account::try_from(&infos[6])?,
+            token_program: Program::try_from(&infos[7])?,
+            system_program: Program::try_from(&infos[8])?,
+        };
+        optimization_log::validator_prove_integrity(
+            Context::new(program_id, &mut accounts, &[], Default::default()),
+            ValidationType::Approve,
+            PUBLIC_INPUTS,
+            MINIMUM_VALIDATOR_STAKE,
+            noir_circuit_id,
+            CIRCUIT_PARAMS_HASH,
+        )?;
+        accounts.exit(program_id)?;
+        Ok(())
+    })
+}
+
+#[test]
+fn the_proof_must_be_the_validators_verified_proof() {
+    let mut bank = Bank::new();
+    let v = setup(&mut bank);
+    let good = bank.proof_buffer(&v.proof_buffer);
+
+    let mut other_owner = good;
+    other_owner.owner = Pubkey::new_unique();
+    let mut other_circuit = good;
+    other_circuit.noir_circuit_id = ENCRYPTION_CIRCUIT_ID;
+    let mut unverified = good;
+    unverified.status = PROOF_BUFFER_VERIFYING;
+    let mut other_input = good;
+    other_input.public_input = [0x43; 32];
+
+    for (buffer, error) in [
+        (other_owner, OptimizationLogError::UnauthorizedProofBuffer),
+        (other_circuit, OptimizationLogError::InvalidCircuitId),
+        (unverified, OptimizationLogError::ProofNotVerified),
+        (other_input, OptimizationLogError::PublicInputMismatch),
+    ] {
+        bank.put_proof_buffer(v.proof_buffer, &buffer);
+        assert_eq!(
+            validate(&mut bank, &v, VALIDATION_CIRCUIT_ID),
+            Err(err(error))
+        );
+    }
+
+    // The validation circuit is the only one a validator proves with.
+    bank.put_proof_buffer(v.proof_buffer, &other_circuit);
+    assert_eq!(
+        validate(&mut bank, &v, ENCRYPTION_CIRCUIT_ID),
+        Err(err(OptimizationLogError::InvalidCircuitForValidationType))
+    );
+}
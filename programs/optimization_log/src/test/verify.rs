@@ -0,0 +1,150 @@
+use super::{
+    err,
+    upload::{governance, one_input_vk, register_vk, upload_proof, write_vk},
+    verified_buffer, Bank, PROOF, SLOT,
+};
+use crate::{
+    accounts, instruction, OptimizationLogError, ENCRYPTION_CIRCUIT_ID, PROOF_BUFFER_VERIFYING,
+    VALIDATION_CIRCUIT_ID,
+};
+use anchor_lang::prelude::*;
+
+/// The first of the `api/stellar/` proof's public inputs.
+const PUBLIC_INPUT: [u8; 32] = {
+    let mut word = [0; 32];
+    word[31] = 1;
+    word
+};
+
+fn step(
+    bank: &mut Bank,
+    proof_buffer: Pubkey,
+    noir_vk: Pubkey,
+    owner: Pubkey,
+) -> std::result::Result<(), ProgramError> {
+    bank.process(
+        accounts::VerifyProofBuffer {
+            proof_buffer,
+            noir_vk,
+            owner,
+        },
+        instruction::VerifyProofBuffer { max_rounds: 1 },
+    )
+}
+
+/// There is no one-public-input fixture, so this steps the `api/stellar/`
+/// proof against its VK declared with one public input: the transcript and
+/// sumcheck round 0 pass, round 1 does not.
+#[test]
+fn steps_resume_from_the_stored_session() {
+    let mut bank = Bank::new();
+    let noir_vk = register_vk(&mut bank, VALIDATION_CIRCUIT_ID);
+    let owner = bank.wallet();
+    let proof_buffer = upload_proof(&mut bank, owner, VALIDATION_CIRCUIT_ID, PUBLIC_INPUT, PROOF);
+
+    step(&mut bank, proof_buffer, noir_vk, owner).unwrap();
+    let after_transcript = bank.proof_buffer(&proof_buffer);
+    assert_eq!(after_transcript.status, PROOF_BUFFER_VERIFYING);
+
+    step(&mut bank, proof_buffer, noir_vk, owner).unwrap();
+    let after_round = bank.proof_buffer(&proof_buffer);
+    assert_eq!(after_round.status, PROOF_BUFFER_VERIFYING);
+    assert_ne!(after_round.session, after_transcript.session);
+
+    assert_eq!(
+        step(&mut bank, proof_buffer, noir_vk, owner),
+        Err(err(OptimizationLogError::InvalidProof))
+    );
+}
+
+#[test]
+fn a_step_needs_a_complete_upload_and_a_registered_vk() {
+    let mut bank = Bank::new();
+    let noir_vk = register_vk(&mut bank, VALIDATION_CIRCUIT_ID);
+    let owner = bank.wallet();
+
+    let partial = upload_proof(
+        &mut bank,
+        owner,
+        VALIDATION_CIRCUIT_ID,
+        PUBLIC_INPUT,
+        &PROOF[..PROOF.len() - 1],
+    );
+    assert_eq!(
+        step(&mut bank, partial, noir_vk, owner),
+        Err(err(OptimizationLogError::ProofIncomplete))
+    );
+
+    let proof_buffer = upload_proof(&mut bank, owner, VALIDATION_CIRCUIT_ID, PUBLIC_INPUT, PROOF);
+    let stranger = bank.wallet();
+    assert_eq!(
+        step(&mut bank, proof_buffer, noir_vk, stranger),
+        Err(err(OptimizationLogError::UnauthorizedProofBuffer))
+    );
+
+    // The VK of another circuit, and one still being uploaded.
+    let gov = governance(&mut bank);
+    let unfinalized = write_vk(&mut bank, &gov, ENCRYPTION_CIRCUIT_ID, &one_input_vk());
+    let encryption_proof =
+        upload_proof(&mut bank, owner, ENCRYPTION_CIRCUIT_ID, PUBLIC_INPUT, PROOF);
+    for vk in [noir_vk, unfinalized] {
+        assert_eq!(
+            step(&mut bank, encryption_proof, vk, owner),
+            Err(err(OptimizationLogError::NoirVkNotRegistered))
+        );
+    }
+}
+
+#[test]
+fn an_expired_buffer_is_not_verified() {
+    let mut bank = Bank::new();
+    let noir_vk = register_vk(&mut bank, VALIDATION_CIRCUIT_ID);
+    let owner = bank.wallet();
+    let proof_buffer = upload_proof(&mut bank, owner, VALIDATION_CIRCUIT_ID, PUBLIC_INPUT, PROOF);
+
+    let mut expired = bank.proof_buffer(&proof_buffer);
+    expired.expires_at = SLOT - 1;
+    bank.put_proof_buffer(proof_buffer, &expired);
+    assert_eq!(
+        step(&mut bank, proof_buffer, noir_vk, owner),
+        Err(err(OptimizationLogError::ProofBufferExpired))
+    );
+}
+
+#[test]
+fn a_malformed_proof_is_rejected() {
+    let mut bank = Bank::new();
+    let noir_vk = register_vk(&mut bank, VALIDATION_CIRCUIT_ID);
+    let owner = bank.wallet();
+    // Moves the first witness commitment off the curve.
+    let mut proof = PROOF.to_vec();
+    proof[16 * 32 + 31] ^= 1;
+    let proof_buffer = upload_proof(
+        &mut bank,
+        owner,
+        VALIDATION_CIRCUIT_ID,
+        PUBLIC_INPUT,
+        &proof,
+    );
+
+    assert_eq!(
+        step(&mut bank, proof_buffer, noir_vk, owner),
+        Err(err(OptimizationLogError::InvalidProof))
+    );
+}
+
+#[test]
+fn a_verified_buffer_is_left_as_it_is() {
+    let mut bank = Bank::new();
+    let noir_vk = register_vk(&mut bank, VALIDATION_CIRCUIT_ID);
+    let owner = bank.wallet();
+    let proof_buffer = Pubkey::new_unique();
+    bank.put_proof_buffer(
+        proof_buffer,
+        &verified_buffer(owner, VALIDATION_CIRCUIT_ID, PUBLIC_INPUT),
+    );
+    let before = bank.state(&proof_buffer).clone();
+
+    step(&mut bank, proof_buffer, noir_vk, owner).unwrap();
+    assert_eq!(bank.state(&proof_buffer), &before);
+}
@@ -0,0 +1,159 @@
+//! Disputes against stored attestations.
+//!
+//! An attestation stays exactly as it was recorded. A dispute is a separate
+//! record under the same submission id: an account the admin has given a
+//! `DisputeRole` opens it with the hash of its off-chain evidence while the
+//! attestation is younger than the dispute window, and a guardian later
+//! resolves it as `Upheld` or `Revoked`. Readers combine the two through
+//! `attestation_status`.
+
+use crate::ttl;
+use soroban_sdk::{contractevent, contracttype, symbol_short, Address, BytesN, Env, Symbol};
+
+/// Default number of ledgers after an attestation during which it can be
+/// disputed (about seven days).
+pub const DEFAULT_WINDOW_LEDGERS: u32 = 120_960;
+
+/// What an account may do with disputes.
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DisputeRole {
+    /// May open disputes.
+    Reporter,
+    /// May open and resolve disputes.
+    Guardian,
+}
+
+/// How a guardian resolved a dispute.
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DisputeResolution {
+    /// The attestation stands.
+    Upheld,
+    /// The attestation is no longer to be relied on.
+    Revoked,
+}
+
+/// Where a dispute stands.
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DisputeState {
+    Open,
+    Resolved(DisputeResolution),
+}
+
+/// A dispute against one attestation.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Dispute {
+    pub submission_id: BytesN<32>,
+    pub reporter: Address,
+    /// Hash of the evidence, which is kept off-chain.
+    pub evidence: BytesN<32>,
+    pub opened_at: u32,
+    pub state: DisputeState,
+    /// `None` while the dispute is open.
+    pub resolved_by: Option<Address>,
+    pub resolved_at: Option<u32>,
+}
+
+/// Where an attestation stands with respect to disputes.
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AttestationStatus {
+    Undisputed,
+    /// A dispute is open.
+    Disputed,
+    Upheld,
+    Revoked,
+}
+
+impl AttestationStatus {
+    pub fn of(dispute: Option<&Dispute>) -> Self {
+        match dispute.map(|d| d.state) {
+            None => AttestationStatus::Undisputed,
+            Some(DisputeState::Open) => AttestationStatus::Disputed,
+            Some(DisputeState::Resolved(DisputeResolution::Upheld)) => AttestationStatus::Upheld,
+            Some(DisputeState::Resolved(DisputeResolution::Revoked)) => AttestationStatus::Revoked,
+        }
+    }
+}
+
+/// Emitted by `open_dispute`.
+#[contractevent(topics = ["DSP_OPEN"], data_format = "vec")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DisputeOpened {
+    #[topic]
+    pub submission_id: BytesN<32>,
+    #[topic]
+    pub reporter: Address,
+    pub evidence: BytesN<32>,
+    pub opened_at: u32,
+}
+
+/// Emitted by `resolve_dispute`.
+#[contractevent(topics = ["DSP_RSLV"], data_format = "vec")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DisputeResolved {
+    #[topic]
+    pub submission_id: BytesN<32>,
+    #[topic]
+    pub guardian: Address,
+    pub resolution: DisputeResolution,
+    pub resolved_at: u32,
+}
+
+fn key_dispute(submission_id: &BytesN<32>) -> (Symbol, BytesN<32>) {
+    (symbol_short!("dispute"), submission_id.clone())
+}
+
+fn key_role(account: &Address) -> (Symbol, Address) {
+    (symbol_short!("disp_role"), account.clone())
+}
+
+fn key_window() -> Symbol {
+    symbol_short!("disp_win")
+}
+
+/// The dispute against `submission_id`, if one was opened.
+pub fn load(env: &Env, submission_id: &BytesN<32>) -> Option<Dispute> {
+    env.storage().persistent().get(&key_dispute(submission_id))
+}
+
+/// Persist `dispute`, with the attestation's TTL.
+pub fn store(env: &Env, dispute: &Dispute) {
+    let key = key_dispute(&dispute.submission_id);
+    env.storage().persistent().set(&key, dispute);
+    ttl::extend(env, &key);
+}
+
+/// Extend the dispute of `submission_id`, if there is one.
+pub fn extend(env: &Env, submission_id: &BytesN<32>) {
+    let key = key_dispute(submission_id);
+    if env.storage().persistent().has(&key) {
+        ttl::extend(env, &key);
+    }
+}
+
+pub fn role(env: &Env, account: &Address) -> Option<DisputeRole> {
+    env.storage().instance().get(&key_role(account))
+}
+
+pub fn set_role(env: &Env, account: &Address, role: Option<DisputeRole>) {
+    match role {
+        Some(role) => env.storage().instance().set(&key_role(account), &role),
+        None => env.storage().instance().remove(&key_role(account)),
+    }
+}
+
+/// Ledgers after an attestation during which it can be disputed.
+pub fn window(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&key_window())
+        .unwrap_or(DEFAULT_WINDOW_LEDGERS)
+}
+
+pub fn set_window(env: &Env, ledgers: u32) {
+    env.storage().instance().set(&key_window(), &ledgers);
+}
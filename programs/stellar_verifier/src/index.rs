@@ -0,0 +1,99 @@
+//! Append-only attestation indexes.
+//!
+//! Every attestation id is appended to three lists: the global list, its
+//! alliance's list and, when it has one, its submitter's list. Each list is
+//! stored as a count plus fixed-size pages of ids in persistent storage, so
+//! an append only rewrites the last page and no single entry grows without
+//! bound.
+
+use crate::ttl;
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, String, Symbol, Vec};
+
+/// Number of ids stored per page entry.
+pub const PAGE_SIZE: u32 = 64;
+
+/// Largest `limit` honoured by a single list call.
+pub const MAX_LIST_LIMIT: u32 = 50;
+
+/// Which list an id is appended to.
+#[contracttype(export = false)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Scope {
+    Global,
+    Alliance(String),
+    Submitter(Address),
+}
+
+fn key_count(scope: &Scope) -> (Symbol, Scope) {
+    (symbol_short!("idx_cnt"), scope.clone())
+}
+
+fn key_page(scope: &Scope, page: u32) -> (Symbol, Scope, u32) {
+    (symbol_short!("idx_page"), scope.clone(), page)
+}
+
+/// Number of ids appended to `scope` so far.
+pub fn count(env: &Env, scope: &Scope) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&key_count(scope))
+        .unwrap_or(0)
+}
+
+/// Append `id` to the end of `scope`.
+pub fn append(env: &Env, scope: &Scope, id: &BytesN<32>) {
+    let n = count(env, scope);
+    let pkey = key_page(scope, n / PAGE_SIZE);
+    let mut page: Vec<BytesN<32>> = env
+        .storage()
+        .persistent()
+        .get(&pkey)
+        .unwrap_or_else(|| Vec::new(env));
+    page.push_back(id.clone());
+    env.storage().persistent().set(&pkey, &page);
+    ttl::extend(env, &pkey);
+
+    let ckey = key_count(scope);
+    env.storage().persistent().set(&ckey, &(n + 1));
+    ttl::extend(env, &ckey);
+}
+
+/// Extend the count of `scope` and the pages holding positions `start` to
+/// `start + limit`, with `limit` clamped like `range`.
+pub fn extend(env: &Env, scope: &Scope, start: u32, limit: u32) {
+    let ckey = key_count(scope);
+    if !env.storage().persistent().has(&ckey) {
+        return;
+    }
+    ttl::extend(env, &ckey);
+    let end = count(env, scope).min(start.saturating_add(limit.min(MAX_LIST_LIMIT)));
+    if start >= end {
+        return;
+    }
+    for page in start / PAGE_SIZE..=(end - 1) / PAGE_SIZE {
+        ttl::extend(env, &key_page(scope, page));
+    }
+}
+
+/// Return up to `limit` ids of `scope` starting at position `start`, oldest
+/// first. `limit` is clamped to `MAX_LIST_LIMIT`.
+pub fn range(env: &Env, scope: &Scope, start: u32, limit: u32) -> Vec<BytesN<32>> {
+    let end = count(env, scope).min(start.saturating_add(limit.min(MAX_LIST_LIMIT)));
+    let mut out = Vec::new(env);
+    let mut loaded: Option<(u32, Vec<BytesN<32>>)> = None;
+    for i in start..end {
+        let page_no = i / PAGE_SIZE;
+        if loaded.as_ref().map(|(p, _)| *p) != Some(page_no) {
+            let page: Vec<BytesN<32>> = env
+                .storage()
+                .persistent()
+                .get(&key_page(scope, page_no))
+                .unwrap_or_else(|| Vec::new(env));
+            loaded = Some((page_no, page));
+        }
+        if let Some(id) = loaded.as_ref().and_then(|(_, page)| page.get(i % PAGE_SIZE)) {
+            out.push_back(id);
+        }
+    }
+    out
+}
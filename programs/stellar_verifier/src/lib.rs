@@ -1,28 +1,376 @@
 #![no_std]
+extern crate alloc;
+
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype,
-    symbol_short, Bytes, BytesN, Env, String, Symbol,
+    contract, contracterror, contractevent, contractimpl, contracttype,
+    symbol_short, Address, Bytes, BytesN, Env, Map, String, Symbol, Vec,
+    crypto::bn254::Bn254Fr,
+};
+use ultrahonk_soroban_verifier::{
+    cost::{conservative_estimate, conservative_session_step, CostEstimate, Phase}, ec::pairing_check, types::PAIRING_POINTS_SIZE, verify_batch_claims, BbVersion, Flavor,
+    OracleHash, PairingClaim, SessionStep, UltraHonkVerifier, VerificationSession, VerifyError,
+    VkLoadError, VkMetadata,
 };
-use ultrahonk_soroban_verifier::{UltraHonkVerifier, VkLoadError, PROOF_BYTES};
+
+mod dispute;
+mod index;
+mod schema;
+mod ttl;
+mod validity;
+
+pub use dispute::{AttestationStatus, Dispute, DisputeResolution, DisputeRole, DisputeState};
+pub use schema::{FieldType, OutputValue};
+pub use ttl::AttestationPresence;
+pub use validity::ValidityScope;
+
+/// Which UltraHonk flavor a circuit's proofs use.
+///
+/// `UltraZk` proofs (bb `--zk`) mask the sumcheck evaluations so they leak
+/// nothing about private inputs; `Ultra` proofs are smaller and cheaper to
+/// verify. The VK bytes are the same for both, so the flavor is declared
+/// alongside the VK.
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ProofFlavor {
+    Ultra,
+    UltraZk,
+}
+
+impl From<ProofFlavor> for Flavor {
+    fn from(f: ProofFlavor) -> Self {
+        match f {
+            ProofFlavor::Ultra => Flavor::Ultra,
+            ProofFlavor::UltraZk => Flavor::UltraZk,
+        }
+    }
+}
+
+/// Transcript hash a circuit's proofs are generated with (bb `--oracle_hash`).
+///
+/// `Keccak` matches the Solidity verifier; `Poseidon2` proofs cost more to
+/// verify here but are cheaper to verify recursively. Like the flavor, this
+/// is not recorded in the VK bytes.
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ProofOracleHash {
+    Keccak,
+    Poseidon2,
+}
+
+impl From<ProofOracleHash> for OracleHash {
+    fn from(h: ProofOracleHash) -> Self {
+        match h {
+            ProofOracleHash::Keccak => OracleHash::Keccak,
+            ProofOracleHash::Poseidon2 => OracleHash::Poseidon2,
+        }
+    }
+}
+
+/// bb release a circuit's VK and proofs are produced with.
+///
+/// bb does not tag its output, and its proof and VK byte layouts change
+/// between releases, so the release is declared alongside the VK. Upgrading
+/// the browser prover means rotating the VK with the new release.
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ProofBbVersion {
+    V0_82_2,
+    V0_87_0,
+}
+
+impl From<ProofBbVersion> for BbVersion {
+    fn from(v: ProofBbVersion) -> Self {
+        match v {
+            ProofBbVersion::V0_82_2 => BbVersion::V0_82_2,
+            ProofBbVersion::V0_87_0 => BbVersion::V0_87_0,
+        }
+    }
+}
+
+/// Deployment-time registration of one circuit: its `bb write_vk` output,
+/// the flavor, transcript hash and bb release its proofs are generated
+/// with, and the typed layout of its public inputs.
+///
+/// The schema must contain exactly one `FieldType::Commitment` entry — the
+/// circuit's `submission_commitment` public input — and at least one
+/// `FieldType::Bool`; the first `Bool` is the circuit's pass/fail output.
+/// It may contain one `FieldType::Ledger`, which circuits with a freshness
+/// window require.
+/// A circuit registered under the id of one in `circuits/` must declare
+/// exactly its layout from the verifier crate's `public_inputs` module.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CircuitConfig {
+    pub vk: Bytes,
+    pub flavor: ProofFlavor,
+    pub oracle_hash: ProofOracleHash,
+    pub bb_version: ProofBbVersion,
+    pub schema: Vec<FieldType>,
+}
+
+impl CircuitConfig {
+    fn meta(&self) -> VkMetadata {
+        VkMetadata {
+            flavor: self.flavor.into(),
+            oracle_hash: self.oracle_hash.into(),
+            bb_version: self.bb_version.into(),
+        }
+    }
+}
+
+/// A registered VK in the form verification loads it, computed once when
+/// the VK is registered: the verifier's canonical encoding of a key that
+/// passed every load check, plus what is derived from the key alone.
+/// Verification rebuilds the key from `key` and loads `constants` without
+/// parsing the registered bytes again, re-checking its 27 commitments or
+/// deriving `log_n` and the public-input delta shifts.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PreparedVk {
+    /// `VerificationKey::to_bytes` (`VK_BYTES` long).
+    pub key: BytesN<1760>,
+    /// `VerificationKey::vk_hash`.
+    pub vk_hash: BytesN<32>,
+    /// `VkConstants::to_bytes` (`VK_CONSTANTS_BYTES` long).
+    pub constants: BytesN<20>,
+}
+
+impl PreparedVk {
+    fn verifier(&self, env: &Env, meta: VkMetadata) -> UltraHonkVerifier {
+        UltraHonkVerifier::new_prepared(
+            env,
+            &self.key.to_array(),
+            &self.constants.to_array(),
+            meta,
+        )
+    }
+}
+
+/// One version of a circuit's registration.
+///
+/// Version 1 comes from the constructor and is active immediately; later
+/// versions are proposed by the admin and take over once the ledger
+/// sequence reaches `activates_at`. Superseded versions stay readable so
+/// historic attestations can be re-checked against the VK they used.
+///
+/// The registered `CircuitConfig` is kept without its `vk` bytes: the key
+/// is stored once, as `prepared.key`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VkVersion {
+    pub version: u32,
+    pub flavor: ProofFlavor,
+    pub oracle_hash: ProofOracleHash,
+    pub bb_version: ProofBbVersion,
+    pub schema: Vec<FieldType>,
+    pub prepared: PreparedVk,
+    pub activates_at: u32,
+}
+
+impl VkVersion {
+    fn new(version: u32, config: CircuitConfig, prepared: PreparedVk, activates_at: u32) -> Self {
+        VkVersion {
+            version,
+            flavor: config.flavor,
+            oracle_hash: config.oracle_hash,
+            bb_version: config.bb_version,
+            schema: config.schema,
+            prepared,
+            activates_at,
+        }
+    }
+
+    fn meta(&self) -> VkMetadata {
+        VkMetadata {
+            flavor: self.flavor.into(),
+            oracle_hash: self.oracle_hash.into(),
+            bb_version: self.bb_version.into(),
+        }
+    }
+}
+
+/// Emitted by `propose_vk`.
+#[contractevent(topics = ["VK_PROP"], data_format = "vec")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VkProposed {
+    #[topic]
+    pub circuit: Symbol,
+    pub version: u32,
+    pub activates_at: u32,
+}
+
+/// Emitted by `cancel_vk`.
+#[contractevent(topics = ["VK_CANCEL"], data_format = "single-value")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VkCancelled {
+    #[topic]
+    pub circuit: Symbol,
+    pub version: u32,
+}
+
+/// Emitted when an attestation is stored.
+#[contractevent(topics = ["ATST"], data_format = "vec")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Attested {
+    #[topic]
+    pub alliance_id: String,
+    #[topic]
+    pub submission_id: BytesN<32>,
+    #[topic]
+    pub submitter: Option<Address>,
+    pub passed: bool,
+    pub outputs: Vec<OutputValue>,
+    pub ledger: u32,
+    pub timestamp: u64,
+    pub circuit: Symbol,
+    pub vk_version: u32,
+}
 
 /// An on-chain attestation created when a ZK proof is verified.
 ///
 /// Each attestation records the **public outputs** of the verified circuit
-/// (whether the optimization passed and at what improvement threshold),
-/// along with metadata (which alliance, which ledger, which timestamp).
+/// (whether the optimization passed, plus every public input decoded
+/// against the circuit's schema), along with metadata (which circuit and
+/// VK version, which alliance, which submitter, which ledger, which
+/// timestamp).
 /// Once created, attestations are immutable and publicly queryable —
 /// they form the on-chain "receipt" for a verified optimization submission.
+/// `valid_until` is fixed from the circuit's and alliance's validity
+/// periods when the attestation is recorded; `is_valid` tells whether it
+/// still holds.
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Attestation {
     pub submission_id: BytesN<32>,
     pub alliance_id: String,
+    pub circuit: Symbol,
+    pub vk_version: u32,
+    pub submitter: Option<Address>,
     pub passed: bool,
-    pub threshold: u32,
+    pub outputs: Vec<OutputValue>,
     pub ledger: u32,
     pub timestamp: u64,
+    /// Last ledger the attestation is valid at; `None` if it does not expire.
+    pub valid_until: Option<u32>,
+}
+
+/// One proof submitted through `verify_and_attest_batch` or
+/// `open_session`; the fields mirror the arguments of `verify_and_attest`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchEntry {
+    pub circuit: Symbol,
+    pub alliance_id: String,
+    pub submission_id: BytesN<32>,
+    pub submitter: Option<Address>,
+    pub public_inputs: Bytes,
+    pub proof: Bytes,
 }
 
+/// Result of one `BatchEntry`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+// Contract types cannot be boxed; test builds see the host-side sizes.
+#[allow(clippy::large_enum_variant)]
+pub enum BatchOutcome {
+    Attested(Attestation),
+    /// The entry failed with this `Error` code; nothing was stored for it.
+    Rejected(u32),
+    /// Not attempted because the batch ran out of budget.
+    Skipped,
+}
+
+/// A verification session opened by `open_session`, for proofs too large to
+/// verify in one transaction.
+///
+/// Everything `verify_and_attest` would attest is fixed when the session is
+/// opened: the circuit and VK version, the submission and its decoded
+/// outputs. `state` is the verifier's saved progress (see
+/// `ultrahonk_soroban_verifier::session`), bound to the proof and public
+/// inputs by their hash. Sessions are kept per `caller`, who alone can
+/// advance or cancel theirs, and cannot be advanced after ledger
+/// `expires_at`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VerifySession {
+    pub caller: Address,
+    pub circuit: Symbol,
+    pub vk_version: u32,
+    pub alliance_id: String,
+    pub submitter: Option<Address>,
+    pub outputs: Vec<OutputValue>,
+    pub passed: bool,
+    pub expires_at: u32,
+    pub stage: SessionStage,
+    pub state: Bytes,
+}
+
+/// The verifier step a session runs next.
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SessionStage {
+    Transcript,
+    /// Sumcheck, resuming at this round.
+    Sumcheck(u32),
+    Relations,
+    Shplemini,
+    Pairing,
+}
+
+impl SessionStage {
+    /// `None` once the proof has verified.
+    fn from_step(step: SessionStep) -> Option<Self> {
+        Some(match step {
+            SessionStep::Transcript => SessionStage::Transcript,
+            SessionStep::Sumcheck { next_round } => SessionStage::Sumcheck(next_round),
+            SessionStep::Relations => SessionStage::Relations,
+            SessionStep::Shplemini => SessionStage::Shplemini,
+            SessionStep::Pairing => SessionStage::Pairing,
+            SessionStep::Done => return None,
+        })
+    }
+
+    fn step(self) -> SessionStep {
+        match self {
+            SessionStage::Transcript => SessionStep::Transcript,
+            SessionStage::Sumcheck(next_round) => SessionStep::Sumcheck { next_round },
+            SessionStage::Relations => SessionStep::Relations,
+            SessionStage::Shplemini => SessionStep::Shplemini,
+            SessionStage::Pairing => SessionStep::Pairing,
+        }
+    }
+}
+
+/// Result of one `advance_session` call.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+// Contract types cannot be boxed; test builds see the host-side sizes.
+#[allow(clippy::large_enum_variant)]
+pub enum SessionOutcome {
+    /// The step ran; this one is next.
+    Pending(SessionStage),
+    /// The proof verified and the attestation was stored.
+    Attested(Attestation),
+}
+
+/// Conservative resources one `verify_and_attest` call is charged (see
+/// `estimate_verify_cost`).
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct VerifyCost {
+    pub cpu_insns: u64,
+    pub mem_bytes: u64,
+}
+
+/// Ledgers a verification session stays open (about one hour).
+pub const SESSION_TTL_LEDGERS: u32 = 720;
+
+/// What `verify_and_attest` spends around the verifier: VM instantiation,
+/// authorization, storage reads, schema decoding, the attestation and index
+/// writes and the event.
+pub const ATTEST_CPU_OVERHEAD: u64 = 5_000_000;
+pub const ATTEST_MEM_OVERHEAD: u64 = 2_000_000;
+
 #[contracterror]
 #[repr(u32)]
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -35,6 +383,166 @@ pub enum Error {
     AlreadyInitialized = 6,
     AlreadyAttested = 7,
     PublicInputsTooShort = 8,
+    InvalidSchema = 9,
+    PublicInputsLengthMismatch = 10,
+    NonCanonicalPublicInput = 11,
+    SubmissionNotBound = 12,
+    VkRotationPending = 13,
+    NoPendingVk = 14,
+    ProofSectionInvalid = 15,
+    PublicInputsMisaligned = 16,
+    DegenerateChallenge = 17,
+    SumcheckRoundFailed = 18,
+    SumcheckFinalMismatch = 19,
+    PairingFailed = 20,
+    VkNonCanonicalPoint = 21,
+    VkPointNotOnCurve = 22,
+    ProofNonCanonicalEncoding = 23,
+    ProofPointNotOnCurve = 24,
+    LibraConsistencyFailed = 25,
+    SessionNotFound = 26,
+    SessionExpired = 27,
+    /// No longer returned: sessions are kept per caller.
+    SessionCallerMismatch = 28,
+    SessionProofMismatch = 29,
+    SessionInProgress = 30,
+    PublicInputOutOfRange = 31,
+    AttestationNotFound = 32,
+    NotDisputeRole = 33,
+    DisputeWindowClosed = 34,
+    DisputeExists = 35,
+    DisputeNotFound = 36,
+    DisputeAlreadyResolved = 37,
+    FreshnessAnchorMissing = 38,
+    ProofNotFresh = 39,
+}
+
+impl From<VkLoadError> for Error {
+    fn from(e: VkLoadError) -> Self {
+        match e {
+            VkLoadError::WrongLength => Error::VkInvalidLength,
+            VkLoadError::InvalidParameters => Error::VkInvalidParameters,
+            VkLoadError::NonCanonicalPoint => Error::VkNonCanonicalPoint,
+            VkLoadError::PointNotOnCurve => Error::VkPointNotOnCurve,
+        }
+    }
+}
+
+impl From<VerifyError> for Error {
+    fn from(e: VerifyError) -> Self {
+        match e {
+            VerifyError::ProofLength { .. } => Error::ProofParseError,
+            VerifyError::ProofSection(_) => Error::ProofSectionInvalid,
+            VerifyError::NonCanonicalEncoding(_) => Error::ProofNonCanonicalEncoding,
+            VerifyError::PointNotOnCurve(_) => Error::ProofPointNotOnCurve,
+            VerifyError::PublicInputsMisaligned { .. } => Error::PublicInputsMisaligned,
+            VerifyError::PublicInputsCount { .. } => Error::PublicInputsLengthMismatch,
+            VerifyError::CircuitSizeOutOfRange { .. } => Error::VkInvalidParameters,
+            VerifyError::ZeroDenominator(_) => Error::DegenerateChallenge,
+            VerifyError::SumcheckRound { .. } => Error::SumcheckRoundFailed,
+            VerifyError::SumcheckFinal => Error::SumcheckFinalMismatch,
+            VerifyError::LibraConsistency => Error::LibraConsistencyFailed,
+            VerifyError::MsmLength => Error::VerificationFailed,
+            VerifyError::PairingFailed => Error::PairingFailed,
+            VerifyError::SessionMismatch => Error::SessionProofMismatch,
+        }
+    }
+}
+
+/// Outcome of the checks `verify_and_attest` runs before the verifier.
+struct Prepared {
+    vk: PreparedVk,
+    meta: VkMetadata,
+    vk_version: u32,
+    outputs: Vec<OutputValue>,
+    passed: bool,
+}
+
+/// An entry that passed its checks and verified up to the pairing, with its
+/// index in the outcomes.
+type Staged = (usize, BatchEntry, Prepared, PairingClaim);
+
+/// Progress of one `verify_and_attest_batch` call: the verifiers loaded so
+/// far, one per circuit, the entries waiting for the combined pairing, and
+/// the instructions charged against the caller's `cpu_limit`.
+struct Batch {
+    cpu_limit: u64,
+    spent: u64,
+    verifiers: alloc::vec::Vec<(Symbol, UltraHonkVerifier)>,
+    pending: alloc::vec::Vec<Staged>,
+}
+
+impl Batch {
+    fn new(cpu_limit: u64) -> Self {
+        Self {
+            cpu_limit,
+            spent: 0,
+            verifiers: alloc::vec::Vec::new(),
+            pending: alloc::vec::Vec::new(),
+        }
+    }
+
+    /// Charge `entry` what `estimate_verify_cost` gives for its circuit and
+    /// proof length, less the VK load once its circuit's verifier is cached,
+    /// and the first entry also the combined pairing check. `Ok(false)`,
+    /// with nothing charged, if that does not fit in the limit.
+    fn charge(&mut self, env: &Env, entry: &BatchEntry) -> Result<bool, Error> {
+        let cached = self.verifiers.iter().any(|(c, _)| *c == entry.circuit);
+        if !cached {
+            let active = OptimizationAttestation::active_version(env, &entry.circuit)?;
+            let verifier = active.prepared.verifier(env, active.meta());
+            self.verifiers.push((entry.circuit.clone(), verifier));
+        }
+        let verifier = self.verifier(&entry.circuit);
+        let estimate = OptimizationAttestation::estimate(verifier, entry.proof.len())?;
+        let mut cost = estimate.cpu_insns() + ATTEST_CPU_OVERHEAD;
+        if cached {
+            cost -= estimate.phase(Phase::VkLoad).cpu_insns;
+        }
+        if self.spent == 0 {
+            cost += estimate.phase(Phase::Pairing).cpu_insns;
+        }
+        if self.spent.saturating_add(cost) > self.cpu_limit {
+            return Ok(false);
+        }
+        self.spent += cost;
+        Ok(true)
+    }
+
+    fn verifier(&self, circuit: &Symbol) -> &UltraHonkVerifier {
+        let (_, verifier) = self
+            .verifiers
+            .iter()
+            .find(|(c, _)| c == circuit)
+            .expect("charged entries have a verifier");
+        verifier
+    }
+
+    /// Verify a charged, prepared entry up to its pairing and queue it for
+    /// the combined check.
+    fn stage(
+        &mut self,
+        env: &Env,
+        slot: usize,
+        entry: BatchEntry,
+        prepared: Prepared,
+    ) -> Result<(), Error> {
+        // Not yet persisted, so `prepare` cannot see in-batch duplicates.
+        if self
+            .pending
+            .iter()
+            .any(|(_, e, _, _)| e.submission_id == entry.submission_id)
+        {
+            return Err(Error::AlreadyAttested);
+        }
+        let claim = self.verifier(&entry.circuit).verify_to_pairing(
+            env,
+            &entry.proof.to_alloc_vec(),
+            &entry.public_inputs.to_alloc_vec(),
+        )?;
+        self.pending.push((slot, entry, prepared, claim));
+        Ok(())
+    }
 }
 
 #[contract]
@@ -42,44 +550,355 @@ pub struct OptimizationAttestation;
 
 #[contractimpl]
 impl OptimizationAttestation {
-    fn key_vk() -> Symbol {
-        symbol_short!("vk")
+    fn key_admin() -> Symbol {
+        symbol_short!("admin")
+    }
+
+    fn key_delay() -> Symbol {
+        symbol_short!("delay")
+    }
+
+    fn key_latest(circuit: &Symbol) -> (Symbol, Symbol) {
+        (symbol_short!("latest"), circuit.clone())
+    }
+
+    fn key_vk(circuit: &Symbol, version: u32) -> (Symbol, Symbol, u32) {
+        (symbol_short!("vk"), circuit.clone(), version)
+    }
+
+    /// Require the admin's authorization.
+    fn require_admin(env: &Env) -> Result<(), Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&Self::key_admin())
+            .ok_or(Error::VkNotSet)?;
+        admin.require_auth();
+        Ok(())
+    }
+
+    fn key_circuits() -> Symbol {
+        symbol_short!("circuits")
+    }
+
+    fn key_session(
+        caller: &Address,
+        submission_id: &BytesN<32>,
+    ) -> (Symbol, Address, BytesN<32>) {
+        (symbol_short!("session"), caller.clone(), submission_id.clone())
     }
 
     fn key_attestation(env: &Env, submission_id: &BytesN<32>) -> Bytes {
         let mut key = Bytes::new(env);
         key.push_back(0x01u8);
-        let mut id_bytes: Bytes = submission_id.clone().into();
-        key.append(&mut id_bytes);
+        let id_bytes: Bytes = submission_id.clone().into();
+        key.append(&id_bytes);
         key
     }
 
+    /// Commitment a proof must carry to be attested under
+    /// `(submission_id, alliance_id, submitter)`:
+    ///
+    /// `keccak256(submission_id ‖ u32_be(len(alliance_id)) ‖ alliance_id ‖ strkey(submitter)) mod r`
+    ///
+    /// The `strkey(submitter)` suffix is omitted for anonymous submissions.
+    /// Because it is a public input, the proof is only valid for this exact
+    /// triple; replaying it under another id, alliance or submitter fails.
+    fn submission_commitment(
+        env: &Env,
+        submission_id: &BytesN<32>,
+        alliance_id: &String,
+        submitter: &Option<Address>,
+    ) -> BytesN<32> {
+        let mut preimage: Bytes = submission_id.clone().into();
+        preimage.extend_from_array(&alliance_id.len().to_be_bytes());
+        preimage.append(&alliance_id.to_bytes());
+        if let Some(submitter) = submitter {
+            preimage.append(&submitter.to_string().to_bytes());
+        }
+        let digest = env.crypto().keccak256(&preimage).to_bytes();
+        Bn254Fr::from_bytes(digest).to_bytes()
+    }
+
+    /// Parse `config.vk` and check that `config.schema` describes exactly
+    /// its public inputs, as the typed layout if `circuit` has one. Returns
+    /// the key prepared for verification.
+    fn validate_config(
+        env: &Env,
+        circuit: &Symbol,
+        config: &CircuitConfig,
+    ) -> Result<PreparedVk, Error> {
+        let verifier = UltraHonkVerifier::new_with_metadata(env, &config.vk.to_alloc_vec(), config.meta())?;
+        let expected = verifier.get_vk().public_inputs_size - PAIRING_POINTS_SIZE as u64;
+        let commitments = config
+            .schema
+            .iter()
+            .filter(|t| *t == FieldType::Commitment)
+            .count();
+        let anchors = config
+            .schema
+            .iter()
+            .filter(|t| *t == FieldType::Ledger)
+            .count();
+        if config.schema.len() as u64 != expected
+            || commitments != 1
+            || anchors > 1
+            || !config.schema.contains(FieldType::Bool)
+        {
+            return Err(Error::InvalidSchema);
+        }
+        if let Some(typed) = schema::typed_circuit(env, circuit) {
+            if !schema::matches_layout(&config.schema, typed) {
+                return Err(Error::InvalidSchema);
+            }
+        }
+        Ok(PreparedVk {
+            key: BytesN::from_array(env, &verifier.get_vk().to_bytes()),
+            vk_hash: BytesN::from_array(env, &verifier.vk_hash()),
+            constants: BytesN::from_array(env, &verifier.constants()?.to_bytes()),
+        })
+    }
+
+    fn latest_version(env: &Env, circuit: &Symbol) -> u32 {
+        env.storage()
+            .instance()
+            .get(&Self::key_latest(circuit))
+            .unwrap_or(0)
+    }
+
+    fn store_version(env: &Env, circuit: &Symbol, record: &VkVersion) {
+        let key = Self::key_vk(circuit, record.version);
+        env.storage().persistent().set(&key, record);
+        ttl::extend(env, &key);
+        env.storage()
+            .instance()
+            .set(&Self::key_latest(circuit), &record.version);
+        ttl::extend_instance(env);
+    }
+
+    /// Newest version of `circuit` whose activation ledger has been reached.
+    fn active_version(env: &Env, circuit: &Symbol) -> Result<VkVersion, Error> {
+        let now = env.ledger().sequence();
+        let mut version = Self::latest_version(env, circuit);
+        while version > 0 {
+            let record: VkVersion = env
+                .storage()
+                .persistent()
+                .get(&Self::key_vk(circuit, version))
+                .ok_or(Error::VkNotSet)?;
+            if record.activates_at <= now {
+                return Ok(record);
+            }
+            version -= 1;
+        }
+        Err(Error::VkNotSet)
+    }
+
     // ── Constructor ────────────────────────────────────────────────
 
-    /// Initialize the contract with a verification key.
-    /// The VK is immutable once set — there is no admin or upgrade path.
-    pub fn __constructor(env: Env, vk_bytes: Bytes) -> Result<(), Error> {
-        if env.storage().instance().has(&Self::key_vk()) {
+    /// Initialize the contract with one verification key and output schema
+    /// per circuit.
+    ///
+    /// `circuits` maps a circuit id (e.g. `benchmark_delta`,
+    /// `data_completeness`) to its `CircuitConfig`. Every VK is parsed up
+    /// front and every schema must describe exactly the VK's public inputs,
+    /// so a malformed entry aborts deployment. These become version 1 of
+    /// each circuit and are active immediately.
+    ///
+    /// `admin` may later rotate VKs through `propose_vk`; a proposal only
+    /// takes effect `activation_delay` ledgers after it is made, giving
+    /// verifiers time to review it.
+    pub fn __constructor(
+        env: Env,
+        admin: Address,
+        activation_delay: u32,
+        circuits: Map<Symbol, CircuitConfig>,
+    ) -> Result<(), Error> {
+        if env.storage().instance().has(&Self::key_circuits()) {
             return Err(Error::AlreadyInitialized);
         }
-        let _ = UltraHonkVerifier::new(&env, &vk_bytes).map_err(|e| match e {
-            VkLoadError::WrongLength => Error::VkInvalidLength,
-            VkLoadError::InvalidParameters => Error::VkInvalidParameters,
-        })?;
-        env.storage().instance().set(&Self::key_vk(), &vk_bytes);
+        if circuits.is_empty() {
+            return Err(Error::VkNotSet);
+        }
+        let now = env.ledger().sequence();
+        let mut ids: Vec<Symbol> = Vec::new(&env);
+        for (circuit, config) in circuits.iter() {
+            let prepared = Self::validate_config(&env, &circuit, &config)?;
+            let record = VkVersion::new(1, config, prepared, now);
+            Self::store_version(&env, &circuit, &record);
+            ids.push_back(circuit);
+        }
+        env.storage().instance().set(&Self::key_circuits(), &ids);
+        env.storage().instance().set(&Self::key_admin(), &admin);
+        env.storage()
+            .instance()
+            .set(&Self::key_delay(), &activation_delay);
+        Ok(())
+    }
+
+    // ── Governance ─────────────────────────────────────────────────
+
+    /// Propose a new VK and schema for `circuit`, returning its version.
+    ///
+    /// Only the admin may call this. The new version activates after the
+    /// configured delay; until then attestations keep using the current
+    /// one. A circuit id that is not yet registered is added with this as
+    /// its first version. At most one proposal per circuit may be pending
+    /// (`VkRotationPending`); withdraw it with `cancel_vk` first.
+    pub fn propose_vk(env: Env, circuit: Symbol, config: CircuitConfig) -> Result<u32, Error> {
+        Self::require_admin(&env)?;
+
+        let prepared = Self::validate_config(&env, &circuit, &config)?;
+        if Self::pending_vk(env.clone(), circuit.clone()).is_some() {
+            return Err(Error::VkRotationPending);
+        }
+
+        let latest = Self::latest_version(&env, &circuit);
+        if latest == 0 {
+            let mut ids = Self::circuits(env.clone());
+            ids.push_back(circuit.clone());
+            env.storage().instance().set(&Self::key_circuits(), &ids);
+        }
+
+        let delay: u32 = env.storage().instance().get(&Self::key_delay()).unwrap_or(0);
+        let record = VkVersion::new(
+            latest + 1,
+            config,
+            prepared,
+            env.ledger().sequence().saturating_add(delay),
+        );
+        Self::store_version(&env, &circuit, &record);
+
+        VkProposed {
+            circuit,
+            version: record.version,
+            activates_at: record.activates_at,
+        }
+        .publish(&env);
+        Ok(record.version)
+    }
+
+    /// Withdraw the pending proposal for `circuit` before it activates.
+    pub fn cancel_vk(env: Env, circuit: Symbol) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+
+        let pending = Self::pending_vk(env.clone(), circuit.clone()).ok_or(Error::NoPendingVk)?;
+        env.storage()
+            .persistent()
+            .remove(&Self::key_vk(&circuit, pending.version));
+        if pending.version == 1 {
+            env.storage().instance().remove(&Self::key_latest(&circuit));
+            let mut ids = Self::circuits(env.clone());
+            if let Some(i) = ids.first_index_of(&circuit) {
+                ids.remove(i);
+            }
+            env.storage().instance().set(&Self::key_circuits(), &ids);
+        } else {
+            env.storage()
+                .instance()
+                .set(&Self::key_latest(&circuit), &(pending.version - 1));
+        }
+
+        VkCancelled {
+            circuit,
+            version: pending.version,
+        }
+        .publish(&env);
         Ok(())
     }
 
     // ── Read Functions ─────────────────────────────────────────────
 
-    /// Return the stored VK bytes for off-chain auditability.
-    pub fn vk_bytes(env: Env) -> Result<Bytes, Error> {
+    /// Return the governance address allowed to rotate VKs.
+    pub fn admin(env: Env) -> Option<Address> {
+        env.storage().instance().get(&Self::key_admin())
+    }
+
+    /// Return the number of ledgers between a VK proposal and its activation.
+    pub fn activation_delay(env: Env) -> u32 {
+        env.storage().instance().get(&Self::key_delay()).unwrap_or(0)
+    }
+
+    /// List the circuit ids that have a registered VK.
+    pub fn circuits(env: Env) -> Vec<Symbol> {
         env.storage()
             .instance()
-            .get(&Self::key_vk())
+            .get(&Self::key_circuits())
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Return the active VK bytes of `circuit` for off-chain auditability,
+    /// in the verifier's canonical encoding (the same bytes as bb's binary
+    /// `vk`).
+    pub fn vk_bytes(env: Env, circuit: Symbol) -> Result<Bytes, Error> {
+        Ok(Self::active_version(&env, &circuit)?.prepared.key.into())
+    }
+
+    /// Return the Keccak-256 fingerprint of the active VK of `circuit`, over
+    /// the verifier's canonical encoding (`VerificationKey::vk_hash`), so
+    /// off-chain tooling can match it against a key imported from any of
+    /// bb's output formats.
+    pub fn vk_hash(env: Env, circuit: Symbol) -> Result<BytesN<32>, Error> {
+        Ok(Self::active_version(&env, &circuit)?.prepared.vk_hash)
+    }
+
+    /// Return a conservative bound on the CPU instructions and memory
+    /// `verify_and_attest` is charged for a `proof_len`-byte proof of
+    /// `circuit`, so relays can set fees and turn away proofs that cannot
+    /// fit in a transaction. The bound depends on the VK and proof format
+    /// only, not on the proof's contents.
+    pub fn estimate_verify_cost(
+        env: Env,
+        circuit: Symbol,
+        proof_len: u32,
+    ) -> Result<VerifyCost, Error> {
+        let active = Self::active_version(&env, &circuit)?;
+        let verifier = active.prepared.verifier(&env, active.meta());
+        let estimate = Self::estimate(&verifier, proof_len)?;
+        Ok(VerifyCost {
+            cpu_insns: estimate.cpu_insns() + ATTEST_CPU_OVERHEAD,
+            mem_bytes: estimate.mem_bytes() + ATTEST_MEM_OVERHEAD,
+        })
+    }
+
+    /// `conservative_estimate` for a `proof_len`-byte proof to `verifier`.
+    fn estimate(verifier: &UltraHonkVerifier, proof_len: u32) -> Result<CostEstimate, Error> {
+        let vk = verifier.get_vk();
+        let format = vk
+            .meta
+            .proof_format(proof_len as usize)
+            .ok_or(Error::ProofParseError)?;
+        Ok(conservative_estimate(vk, format))
+    }
+
+    /// Return the active public-input schema of `circuit`.
+    pub fn output_schema(env: Env, circuit: Symbol) -> Result<Vec<FieldType>, Error> {
+        Ok(Self::active_version(&env, &circuit)?.schema)
+    }
+
+    /// Return the version of `circuit` that new attestations verify against.
+    pub fn vk_version(env: Env, circuit: Symbol) -> Result<VkVersion, Error> {
+        Self::active_version(&env, &circuit)
+    }
+
+    /// Return a specific, possibly superseded or pending, version of `circuit`.
+    pub fn vk_version_at(env: Env, circuit: Symbol, version: u32) -> Result<VkVersion, Error> {
+        env.storage()
+            .persistent()
+            .get(&Self::key_vk(&circuit, version))
             .ok_or(Error::VkNotSet)
     }
 
+    /// Return the proposed version of `circuit` that has not activated yet.
+    pub fn pending_vk(env: Env, circuit: Symbol) -> Option<VkVersion> {
+        let latest = Self::latest_version(&env, &circuit);
+        let record: VkVersion = env
+            .storage()
+            .persistent()
+            .get(&Self::key_vk(&circuit, latest))?;
+        (record.activates_at > env.ledger().sequence()).then_some(record)
+    }
+
     /// Look up an attestation by its submission_id.
     /// Returns `None` if no attestation exists for that ID.
     pub fn get_attestation(env: Env, submission_id: BytesN<32>) -> Option<Attestation> {
@@ -87,88 +906,865 @@ impl OptimizationAttestation {
         env.storage().persistent().get(&key)
     }
 
+    fn load_page(env: &Env, scope: &index::Scope, start: u32, limit: u32) -> Vec<Attestation> {
+        let mut out = Vec::new(env);
+        for id in index::range(env, scope, start, limit).iter() {
+            if let Some(attestation) = Self::get_attestation(env.clone(), id) {
+                out.push_back(attestation);
+            }
+        }
+        out
+    }
+
+    /// List attestations of `alliance_id` in creation order, starting at
+    /// position `start`. At most 50 entries are returned per call.
+    pub fn list_attestations(
+        env: Env,
+        alliance_id: String,
+        start: u32,
+        limit: u32,
+    ) -> Vec<Attestation> {
+        Self::load_page(&env, &index::Scope::Alliance(alliance_id), start, limit)
+    }
+
+    /// List attestations made by `submitter`, paginated like `list_attestations`.
+    pub fn list_attestations_by_submitter(
+        env: Env,
+        submitter: Address,
+        start: u32,
+        limit: u32,
+    ) -> Vec<Attestation> {
+        Self::load_page(&env, &index::Scope::Submitter(submitter), start, limit)
+    }
+
+    /// List all attestations, paginated like `list_attestations`.
+    pub fn list_all_attestations(env: Env, start: u32, limit: u32) -> Vec<Attestation> {
+        Self::load_page(&env, &index::Scope::Global, start, limit)
+    }
+
+    /// Number of attestations recorded for `alliance_id`.
+    pub fn attestation_count(env: Env, alliance_id: String) -> u32 {
+        index::count(&env, &index::Scope::Alliance(alliance_id))
+    }
+
+    /// Number of attestations recorded for `submitter`.
+    pub fn submitter_attestation_count(env: Env, submitter: Address) -> u32 {
+        index::count(&env, &index::Scope::Submitter(submitter))
+    }
+
+    /// Number of attestations recorded across all alliances.
+    pub fn total_attestations(env: Env) -> u32 {
+        index::count(&env, &index::Scope::Global)
+    }
+
     /// Check whether a submission_id has already been attested.
+    ///
+    /// `false` for an attestation that has been archived; use
+    /// `attestation_presence` to tell that apart from one that never
+    /// existed.
     pub fn has_attestation(env: Env, submission_id: BytesN<32>) -> bool {
         let key = Self::key_attestation(&env, &submission_id);
         env.storage().persistent().has(&key)
     }
 
+    /// Whether the attestation of `submission_id` is live, archived (and
+    /// must be restored before it can be read or bumped) or missing.
+    pub fn attestation_presence(env: Env, submission_id: BytesN<32>) -> AttestationPresence {
+        if Self::has_attestation(env.clone(), submission_id.clone()) {
+            AttestationPresence::Live
+        } else if ttl::was_attested(&env, &submission_id) {
+            AttestationPresence::Archived
+        } else {
+            AttestationPresence::Missing
+        }
+    }
+
     // ── Stateful Verify + Attest ───────────────────────────────────
 
-    /// Verify an UltraHonk proof and, on success, **store** a permanent
-    /// attestation on-chain. The attestation captures the circuit's
-    /// public outputs (passed / threshold) plus ledger metadata.
+    /// Every check `verify_and_attest` runs before the verifier itself:
+    /// duplicate id, active VK, proof length, schema decoding, freshness and
+    /// submission binding.
+    fn prepare(
+        env: &Env,
+        circuit: &Symbol,
+        alliance_id: &String,
+        submission_id: &BytesN<32>,
+        submitter: &Option<Address>,
+        public_inputs: &Bytes,
+        proof_bytes: &Bytes,
+    ) -> Result<Prepared, Error> {
+        // ── Re-entrancy guard: no double-attestation ───────────────
+        // An archived attestation still counts while its marker is live.
+        if env
+            .storage()
+            .persistent()
+            .has(&Self::key_attestation(env, submission_id))
+            || ttl::was_attested(env, submission_id)
+        {
+            return Err(Error::AlreadyAttested);
+        }
+
+        // ── Load VK ────────────────────────────────────────────────
+        let active = Self::active_version(env, circuit)?;
+        ttl::extend(env, &Self::key_vk(circuit, active.version));
+        let schema = &active.schema;
+
+        // ── Length check for the circuit's proof layout ───────────
+        // Either as bb wrote it or with G1 points compressed; the verifier
+        // tells the two apart by length.
+        let meta = active.meta();
+        if meta.proof_format(proof_bytes.len() as usize).is_none() {
+            return Err(Error::ProofParseError);
+        }
+
+        // ── Decode public outputs against the schema ──────────────
+        // Rejects wrong word counts and non-canonical encodings before
+        // paying for verification.
+        let outputs = schema::decode(env, schema, public_inputs)?;
+        schema::check_ranges(env, circuit, schema, public_inputs)?;
+
+        // ── Freshness ──────────────────────────────────────────────
+        if let Some(max_age) = validity::freshness(env, circuit) {
+            validity::extend_freshness(env, circuit);
+            let anchor = schema::anchor_ledger(&outputs).ok_or(Error::FreshnessAnchorMissing)?;
+            if !validity::is_fresh(env, anchor, max_age) {
+                return Err(Error::ProofNotFresh);
+            }
+        }
+
+        // ── Submission binding ─────────────────────────────────────
+        let expected = OutputValue::Field(Self::submission_commitment(
+            env,
+            submission_id,
+            alliance_id,
+            submitter,
+        ));
+        let bound = schema
+            .iter()
+            .zip(outputs.iter())
+            .any(|(ty, value)| ty == FieldType::Commitment && value == expected);
+        if !bound {
+            return Err(Error::SubmissionNotBound);
+        }
+
+        let passed = schema
+            .iter()
+            .zip(outputs.iter())
+            .find(|(ty, _)| *ty == FieldType::Bool)
+            .is_some_and(|(_, value)| value == OutputValue::Bool(true));
+
+        Ok(Prepared {
+            vk_version: active.version,
+            vk: active.prepared,
+            meta,
+            outputs,
+            passed,
+        })
+    }
+
+    /// Persist, index and announce an attestation for a verified proof.
+    fn record(
+        env: &Env,
+        circuit: Symbol,
+        alliance_id: String,
+        submission_id: BytesN<32>,
+        submitter: Option<Address>,
+        prepared: Prepared,
+    ) -> Attestation {
+        let Prepared {
+            vk_version,
+            outputs,
+            passed,
+            ..
+        } = prepared;
+
+        // ── Build attestation ──────────────────────────────────────
+        let attestation = Attestation {
+            submission_id: submission_id.clone(),
+            alliance_id: alliance_id.clone(),
+            circuit: circuit.clone(),
+            vk_version,
+            submitter: submitter.clone(),
+            passed,
+            outputs: outputs.clone(),
+            ledger: env.ledger().sequence(),
+            timestamp: env.ledger().timestamp(),
+            valid_until: validity::valid_until(env, &circuit, &alliance_id),
+        };
+
+        // ── Persist ────────────────────────────────────────────────
+        let akey = Self::key_attestation(env, &submission_id);
+        env.storage().persistent().set(&akey, &attestation);
+        ttl::extend(env, &akey);
+        ttl::mark_attested(env, &submission_id);
+        ttl::extend_instance(env);
+
+        index::append(env, &index::Scope::Global, &submission_id);
+        index::append(
+            env,
+            &index::Scope::Alliance(alliance_id.clone()),
+            &submission_id,
+        );
+        if let Some(submitter) = &submitter {
+            index::append(
+                env,
+                &index::Scope::Submitter(submitter.clone()),
+                &submission_id,
+            );
+        }
+
+        // ── Emit event ────────────────────────────────────────────
+        Attested {
+            alliance_id,
+            submission_id,
+            submitter,
+            passed,
+            outputs,
+            ledger: attestation.ledger,
+            timestamp: attestation.timestamp,
+            circuit,
+            vk_version,
+        }
+        .publish(env);
+
+        attestation
+    }
+
+    /// Verify an UltraHonk proof against the active VK version of `circuit`
+    /// and, on success, **store** a permanent attestation on-chain. The
+    /// attestation captures the circuit's public inputs, decoded against
+    /// the registered schema, plus ledger metadata.
+    ///
+    /// The proof must carry `submission_commitment(submission_id,
+    /// alliance_id, submitter)` in its commitment public input, otherwise
+    /// the call fails with `SubmissionNotBound`.
+    ///
+    /// `proof_bytes` is either bb's output or its compressed form
+    /// (`ultrahonk_soroban_verifier::utils::compress_proof`), which is
+    /// about a quarter smaller but costs a square root per G1 point to load.
+    ///
+    /// When `submitter` is given it must authorize the call; the attestation
+    /// is then recorded under that account so agents can accumulate
+    /// reputation. `None` creates an anonymous attestation.
     ///
     /// Every `submission_id` can be attested at most once (returns
     /// `AlreadyAttested` on duplicate).
     pub fn verify_and_attest(
         env: Env,
+        circuit: Symbol,
         alliance_id: String,
         submission_id: BytesN<32>,
+        submitter: Option<Address>,
         public_inputs: Bytes,
         proof_bytes: Bytes,
     ) -> Result<Attestation, Error> {
-        // ── Length checks ──────────────────────────────────────────
-        if proof_bytes.len() as usize != PROOF_BYTES {
-            return Err(Error::ProofParseError);
+        if let Some(submitter) = &submitter {
+            submitter.require_auth();
         }
-        if public_inputs.len() < 64 {
-            return Err(Error::PublicInputsTooShort);
+
+        let prepared = Self::prepare(
+            &env,
+            &circuit,
+            &alliance_id,
+            &submission_id,
+            &submitter,
+            &public_inputs,
+            &proof_bytes,
+        )?;
+
+        let verifier = prepared.vk.verifier(&env, prepared.meta);
+
+        // ── ZK verification ───────────────────────────────────────
+        verifier.verify(
+            &env,
+            &proof_bytes.to_alloc_vec(),
+            &public_inputs.to_alloc_vec(),
+        )?;
+
+        Ok(Self::record(
+            &env,
+            circuit,
+            alliance_id,
+            submission_id,
+            submitter,
+            prepared,
+        ))
+    }
+
+    /// Verify and attest several proofs in one invocation.
+    ///
+    /// Entries are processed in order and each gets its own outcome; a
+    /// rejected entry does not affect the others. Each circuit's prepared VK is
+    /// loaded once per call and shared by all of its entries.
+    ///
+    /// The pairings of all entries that pass the cheaper checks are combined
+    /// into a single pairing check. Only if that fails are the pairings
+    /// re-checked one by one to find the invalid entries.
+    ///
+    /// A contract cannot read its remaining budget, so the caller passes the
+    /// instruction limit it declared for the transaction as `cpu_limit`.
+    /// Before its checks run, every entry is charged against it what
+    /// `estimate_verify_cost` gives for its circuit and proof, less the VK
+    /// load for circuits already loaded in this call, and the first entry
+    /// also the combined pairing. Once the next entry would not fit, it and
+    /// all later ones come back `Skipped` and can be resubmitted.
+    ///
+    /// Submitters named in the entries must authorize the call; a missing
+    /// authorization aborts the whole transaction.
+    pub fn verify_and_attest_batch(
+        env: Env,
+        entries: Vec<BatchEntry>,
+        cpu_limit: u64,
+    ) -> Vec<BatchOutcome> {
+        let mut batch = Batch::new(cpu_limit);
+        let mut outcomes: alloc::vec::Vec<Option<BatchOutcome>> = alloc::vec::Vec::new();
+        let mut exhausted = false;
+
+        // ── Per-entry checks, sumcheck and Shplemini ──────────────
+        for entry in entries.iter() {
+            if !exhausted {
+                match batch.charge(&env, &entry) {
+                    Ok(fits) => exhausted = !fits,
+                    Err(e) => {
+                        outcomes.push(Some(BatchOutcome::Rejected(e as u32)));
+                        continue;
+                    }
+                }
+            }
+            if exhausted {
+                outcomes.push(Some(BatchOutcome::Skipped));
+                continue;
+            }
+            if let Some(submitter) = &entry.submitter {
+                submitter.require_auth();
+            }
+
+            let slot = outcomes.len();
+            let staged = Self::prepare(
+                &env,
+                &entry.circuit,
+                &entry.alliance_id,
+                &entry.submission_id,
+                &entry.submitter,
+                &entry.public_inputs,
+                &entry.proof,
+            )
+            .and_then(|prepared| batch.stage(&env, slot, entry, prepared));
+            outcomes.push(match staged {
+                Ok(()) => None,
+                Err(e) => Some(BatchOutcome::Rejected(e as u32)),
+            });
         }
 
-        // ── Re-entrancy guard: no double-attestation ───────────────
-        let akey = Self::key_attestation(&env, &submission_id);
-        if env.storage().persistent().has(&akey) {
+        // ── One combined pairing, per-entry fallback on failure ────
+        let claims: alloc::vec::Vec<PairingClaim> =
+            batch.pending.iter().map(|(_, _, _, c)| c.clone()).collect();
+        let all_valid = verify_batch_claims(&env, &claims).is_ok();
+
+        for (slot, entry, prepared, claim) in batch.pending {
+            outcomes[slot] = Some(
+                if all_valid || pairing_check(&env, &claim.p0, &claim.p1) {
+                    BatchOutcome::Attested(Self::record(
+                        &env,
+                        entry.circuit,
+                        entry.alliance_id,
+                        entry.submission_id,
+                        entry.submitter,
+                        prepared,
+                    ))
+                } else {
+                    BatchOutcome::Rejected(Error::PairingFailed as u32)
+                },
+            );
+        }
+
+        let mut out = Vec::new(&env);
+        for outcome in outcomes.into_iter().flatten() {
+            out.push_back(outcome);
+        }
+        out
+    }
+
+    // ── Multi-transaction Verification ─────────────────────────────
+
+    fn store_session(env: &Env, submission_id: &BytesN<32>, session: &VerifySession) {
+        let key = Self::key_session(&session.caller, submission_id);
+        let remaining = session.expires_at.saturating_sub(env.ledger().sequence());
+        env.storage().temporary().set(&key, session);
+        env.storage()
+            .temporary()
+            .extend_ttl(&key, remaining, remaining);
+    }
+
+    /// The session `caller` opened for `submission_id`.
+    fn caller_session(
+        env: &Env,
+        caller: &Address,
+        submission_id: &BytesN<32>,
+    ) -> Result<VerifySession, Error> {
+        env.storage()
+            .temporary()
+            .get(&Self::key_session(caller, submission_id))
+            .ok_or(Error::SessionNotFound)
+    }
+
+    /// Start verifying a proof over several transactions, for circuits whose
+    /// verification does not fit one transaction's budget.
+    ///
+    /// Runs every check `verify_and_attest` runs before the verifier
+    /// (duplicate id, active VK, proof length, schema, submission binding)
+    /// and fixes their outcome in the session. The proof is then verified
+    /// by repeated `advance_session` calls from `caller`, which must finish
+    /// within `SESSION_TTL_LEDGERS` ledgers.
+    ///
+    /// Sessions are kept per `(caller, submission_id)`, so opening one does
+    /// not hold the submission against anyone else: whichever session
+    /// finishes first attests, and the others then fail with
+    /// `AlreadyAttested`. A caller has one live session per submission
+    /// (`SessionInProgress`); an expired one is replaced.
+    pub fn open_session(
+        env: Env,
+        caller: Address,
+        entry: BatchEntry,
+    ) -> Result<VerifySession, Error> {
+        caller.require_auth();
+        let BatchEntry {
+            circuit,
+            alliance_id,
+            submission_id,
+            submitter,
+            public_inputs,
+            proof: proof_bytes,
+        } = entry;
+        if let Some(submitter) = &submitter {
+            submitter.require_auth();
+        }
+
+        let existing: Option<VerifySession> = env
+            .storage()
+            .temporary()
+            .get(&Self::key_session(&caller, &submission_id));
+        if existing.is_some_and(|s| s.expires_at >= env.ledger().sequence()) {
+            return Err(Error::SessionInProgress);
+        }
+
+        let prepared = Self::prepare(
+            &env,
+            &circuit,
+            &alliance_id,
+            &submission_id,
+            &submitter,
+            &public_inputs,
+            &proof_bytes,
+        )?;
+
+        let state = VerificationSession::new(
+            &env,
+            &proof_bytes.to_alloc_vec(),
+            &public_inputs.to_alloc_vec(),
+        );
+        let session = VerifySession {
+            caller,
+            circuit,
+            vk_version: prepared.vk_version,
+            alliance_id,
+            submitter,
+            outputs: prepared.outputs,
+            passed: prepared.passed,
+            expires_at: env.ledger().sequence() + SESSION_TTL_LEDGERS,
+            stage: SessionStage::Transcript,
+            state: Bytes::from_slice(&env, &state.to_bytes()),
+        };
+        Self::store_session(&env, &submission_id, &session);
+        Ok(session)
+    }
+
+    /// Run the next verifier step of the session of `submission_id`: the
+    /// transcript, up to `max_rounds` sumcheck rounds, the relations,
+    /// Shplemini or the pairing. The proof and public inputs must be the
+    /// ones the session was opened with (`SessionProofMismatch`).
+    ///
+    /// After the pairing the attestation is stored exactly as
+    /// `verify_and_attest` would store it, and the session is closed. A
+    /// failing step leaves the session unchanged; since every step is
+    /// deterministic, a proof that fails one will never pass, and the
+    /// session should be cancelled.
+    pub fn advance_session(
+        env: Env,
+        caller: Address,
+        submission_id: BytesN<32>,
+        public_inputs: Bytes,
+        proof_bytes: Bytes,
+        max_rounds: u32,
+    ) -> Result<SessionOutcome, Error> {
+        caller.require_auth();
+        let mut session = Self::caller_session(&env, &caller, &submission_id)?;
+        if env.ledger().sequence() > session.expires_at {
+            return Err(Error::SessionExpired);
+        }
+
+        let record: VkVersion = env
+            .storage()
+            .persistent()
+            .get(&Self::key_vk(&session.circuit, session.vk_version))
+            .ok_or(Error::VkNotSet)?;
+        let meta = record.meta();
+        let verifier = record.prepared.verifier(&env, meta);
+        let mut state = VerificationSession::from_bytes(&env, &session.state.to_alloc_vec())
+            .ok_or(Error::SessionNotFound)?;
+
+        let step = verifier.advance_session(
+            &env,
+            &mut state,
+            &proof_bytes.to_alloc_vec(),
+            &public_inputs.to_alloc_vec(),
+            max_rounds,
+        )?;
+
+        if let Some(stage) = SessionStage::from_step(step) {
+            session.stage = stage;
+            session.state = Bytes::from_slice(&env, &state.to_bytes());
+            Self::store_session(&env, &submission_id, &session);
+            return Ok(SessionOutcome::Pending(stage));
+        }
+
+        // ── Verified: attest ───────────────────────────────────────
+        env.storage()
+            .temporary()
+            .remove(&Self::key_session(&caller, &submission_id));
+        // As in `prepare`, an archived attestation still counts.
+        if env
+            .storage()
+            .persistent()
+            .has(&Self::key_attestation(&env, &submission_id))
+            || ttl::was_attested(&env, &submission_id)
+        {
             return Err(Error::AlreadyAttested);
         }
+        let prepared = Prepared {
+            vk: record.prepared,
+            meta,
+            vk_version: session.vk_version,
+            outputs: session.outputs,
+            passed: session.passed,
+        };
+        Ok(SessionOutcome::Attested(Self::record(
+            &env,
+            session.circuit,
+            session.alliance_id,
+            submission_id,
+            session.submitter,
+            prepared,
+        )))
+    }
 
-        // ── Load VK ────────────────────────────────────────────────
-        let vk_bytes: Bytes = env
+    /// Discard `caller`'s session of `submission_id`, e.g. after a failed
+    /// step.
+    pub fn cancel_session(
+        env: Env,
+        caller: Address,
+        submission_id: BytesN<32>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+        Self::caller_session(&env, &caller, &submission_id)?;
+        env.storage()
+            .temporary()
+            .remove(&Self::key_session(&caller, &submission_id));
+        Ok(())
+    }
+
+    /// Return `caller`'s open (possibly expired) session of `submission_id`.
+    pub fn get_session(
+        env: Env,
+        caller: Address,
+        submission_id: BytesN<32>,
+    ) -> Option<VerifySession> {
+        env.storage()
+            .temporary()
+            .get(&Self::key_session(&caller, &submission_id))
+    }
+
+    /// Conservative resources the next `advance_session` call on `caller`'s
+    /// session of `submission_id` is charged, for a proof of `proof_len`
+    /// bytes and up to `max_rounds` sumcheck rounds. Each step hashes and
+    /// re-reads the proof; this includes that, and the attestation a final
+    /// step stores.
+    pub fn estimate_session_step_cost(
+        env: Env,
+        caller: Address,
+        submission_id: BytesN<32>,
+        proof_len: u32,
+        max_rounds: u32,
+    ) -> Result<VerifyCost, Error> {
+        let session = Self::caller_session(&env, &caller, &submission_id)?;
+        let record: VkVersion = env
             .storage()
-            .instance()
-            .get(&Self::key_vk())
+            .persistent()
+            .get(&Self::key_vk(&session.circuit, session.vk_version))
             .ok_or(Error::VkNotSet)?;
+        let meta = record.meta();
+        let format = meta
+            .proof_format(proof_len as usize)
+            .ok_or(Error::ProofParseError)?;
+        let verifier = record.prepared.verifier(&env, meta);
+        let estimate = conservative_session_step(
+            verifier.get_vk(),
+            format,
+            session.stage.step(),
+            max_rounds,
+        );
+        Ok(VerifyCost {
+            cpu_insns: estimate.cpu_insns() + ATTEST_CPU_OVERHEAD,
+            mem_bytes: estimate.mem_bytes() + ATTEST_MEM_OVERHEAD,
+        })
+    }
 
-        let verifier = UltraHonkVerifier::new(&env, &vk_bytes).map_err(|e| match e {
-            VkLoadError::WrongLength => Error::VkInvalidLength,
-            VkLoadError::InvalidParameters => Error::VkInvalidParameters,
-        })?;
+    // ── Disputes ───────────────────────────────────────────────────
 
-        // ── ZK verification ───────────────────────────────────────
-        verifier
-            .verify(&env, &proof_bytes, &public_inputs)
-            .map_err(|_| Error::VerificationFailed)?;
+    /// Grant `account` a dispute role, or revoke it with `None`. Admin only.
+    pub fn set_dispute_role(
+        env: Env,
+        account: Address,
+        role: Option<DisputeRole>,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        dispute::set_role(&env, &account, role);
+        Ok(())
+    }
 
-        // ── Parse public outputs from the circuit ─────────────────
-        // The circuit returns 2 × 32-byte BN254 field elements:
-        //   [0..32]  → passed (1 = true, 0 = false)
-        //   [32..64] → min_improvement_percent (u8 in last byte)
-        let passed_byte = public_inputs.get(31).unwrap_or(0);
-        let passed = passed_byte != 0;
-        let threshold = public_inputs.get(63).unwrap_or(0) as u32;
+    /// Return the dispute role of `account`, if it has one.
+    pub fn dispute_role(env: Env, account: Address) -> Option<DisputeRole> {
+        dispute::role(&env, &account)
+    }
 
-        // ── Build attestation ──────────────────────────────────────
-        let attestation = Attestation {
+    /// Set how many ledgers after an attestation it can still be disputed.
+    /// Admin only; applies to existing attestations too.
+    pub fn set_dispute_window(env: Env, ledgers: u32) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        dispute::set_window(&env, ledgers);
+        Ok(())
+    }
+
+    /// Return the dispute window in ledgers.
+    pub fn dispute_window(env: Env) -> u32 {
+        dispute::window(&env)
+    }
+
+    /// Dispute the attestation of `submission_id`, citing the hash of
+    /// off-chain `evidence`.
+    ///
+    /// `reporter` must hold a `DisputeRole` and authorize the call, and the
+    /// attestation must be at most `dispute_window` ledgers old. Each
+    /// attestation can be disputed once; the attestation itself is left
+    /// unchanged. Emits `DSP_OPEN`.
+    pub fn open_dispute(
+        env: Env,
+        reporter: Address,
+        submission_id: BytesN<32>,
+        evidence: BytesN<32>,
+    ) -> Result<Dispute, Error> {
+        reporter.require_auth();
+        if dispute::role(&env, &reporter).is_none() {
+            return Err(Error::NotDisputeRole);
+        }
+        let attestation = Self::get_attestation(env.clone(), submission_id.clone())
+            .ok_or(Error::AttestationNotFound)?;
+        let now = env.ledger().sequence();
+        if now > attestation.ledger.saturating_add(dispute::window(&env)) {
+            return Err(Error::DisputeWindowClosed);
+        }
+        if dispute::load(&env, &submission_id).is_some() {
+            return Err(Error::DisputeExists);
+        }
+
+        let record = Dispute {
             submission_id: submission_id.clone(),
-            alliance_id: alliance_id.clone(),
-            passed,
-            threshold,
-            ledger: env.ledger().sequence(),
-            timestamp: env.ledger().timestamp(),
+            reporter: reporter.clone(),
+            evidence: evidence.clone(),
+            opened_at: now,
+            state: DisputeState::Open,
+            resolved_by: None,
+            resolved_at: None,
         };
+        dispute::store(&env, &record);
+        dispute::DisputeOpened {
+            submission_id,
+            reporter,
+            evidence,
+            opened_at: now,
+        }
+        .publish(&env);
+        Ok(record)
+    }
 
-        // ── Persist ────────────────────────────────────────────────
-        env.storage().persistent().set(&akey, &attestation);
-        env.storage()
-            .persistent()
-            .extend_ttl(&akey, 100_000, 535_679);
+    /// Resolve the open dispute of `submission_id`. `guardian` must hold
+    /// `DisputeRole::Guardian` and authorize the call. Emits `DSP_RSLV`.
+    pub fn resolve_dispute(
+        env: Env,
+        guardian: Address,
+        submission_id: BytesN<32>,
+        resolution: DisputeResolution,
+    ) -> Result<Dispute, Error> {
+        guardian.require_auth();
+        if dispute::role(&env, &guardian) != Some(DisputeRole::Guardian) {
+            return Err(Error::NotDisputeRole);
+        }
+        let mut record = dispute::load(&env, &submission_id).ok_or(Error::DisputeNotFound)?;
+        if record.state != DisputeState::Open {
+            return Err(Error::DisputeAlreadyResolved);
+        }
 
-        // ── Emit event ────────────────────────────────────────────
-        env.events().publish(
-            (symbol_short!("ATST"), alliance_id, submission_id),
-            (passed, threshold, attestation.ledger, attestation.timestamp),
-        );
+        let now = env.ledger().sequence();
+        record.state = DisputeState::Resolved(resolution);
+        record.resolved_by = Some(guardian.clone());
+        record.resolved_at = Some(now);
+        dispute::store(&env, &record);
+        dispute::DisputeResolved {
+            submission_id,
+            guardian,
+            resolution,
+            resolved_at: now,
+        }
+        .publish(&env);
+        Ok(record)
+    }
+
+    /// Return the dispute of `submission_id`, open or resolved.
+    pub fn get_dispute(env: Env, submission_id: BytesN<32>) -> Option<Dispute> {
+        dispute::load(&env, &submission_id)
+    }
+
+    /// Return where the attestation of `submission_id` stands with respect
+    /// to disputes, or `None` if there is no such attestation.
+    pub fn attestation_status(env: Env, submission_id: BytesN<32>) -> Option<AttestationStatus> {
+        if !Self::has_attestation(env.clone(), submission_id.clone()) {
+            return None;
+        }
+        Some(AttestationStatus::of(
+            dispute::load(&env, &submission_id).as_ref(),
+        ))
+    }
 
-        Ok(attestation)
+    // ── Validity ───────────────────────────────────────────────────
+
+    /// Limit attestations of a circuit or alliance to `ledgers` after they
+    /// are recorded, or lift the limit with `None`. Admin only. Only
+    /// attestations recorded afterwards are affected; when both the circuit
+    /// and the alliance have a period, the shorter applies.
+    pub fn set_validity_period(
+        env: Env,
+        scope: ValidityScope,
+        ledgers: Option<u32>,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        validity::set_period(&env, &scope, ledgers);
+        Ok(())
+    }
+
+    /// Return the validity period of `scope` in ledgers, if it has one.
+    pub fn validity_period(env: Env, scope: ValidityScope) -> Option<u32> {
+        validity::period(&env, &scope)
+    }
+
+    /// Require proofs of `circuit` to carry a `FieldType::Ledger` public
+    /// input at most `ledgers` old, or drop the requirement with `None`.
+    /// Admin only.
+    pub fn set_freshness_window(
+        env: Env,
+        circuit: Symbol,
+        ledgers: Option<u32>,
+    ) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        validity::set_freshness(&env, &circuit, ledgers);
+        Ok(())
+    }
+
+    /// Return the freshness window of `circuit` in ledgers, if it has one.
+    pub fn freshness_window(env: Env, circuit: Symbol) -> Option<u32> {
+        validity::freshness(&env, &circuit)
+    }
+
+    /// Whether the attestation of `submission_id` can be relied on now:
+    /// it exists, has not passed its `valid_until` and has not been revoked
+    /// through a dispute. An open dispute does not invalidate it.
+    pub fn is_valid(env: Env, submission_id: BytesN<32>) -> bool {
+        let Some(attestation) = Self::get_attestation(env.clone(), submission_id.clone()) else {
+            return false;
+        };
+        let expired = attestation
+            .valid_until
+            .is_some_and(|until| env.ledger().sequence() > until);
+        let status = AttestationStatus::of(dispute::load(&env, &submission_id).as_ref());
+        !expired && status != AttestationStatus::Revoked
+    }
+
+    // ── TTL ────────────────────────────────────────────────────────
+
+    /// Extend the attestation of `submission_id` by `ttl::EXTEND_TO`
+    /// ledgers, together with its marker and dispute. Anyone may call this
+    /// and pays the rent. An archived attestation must be restored first.
+    pub fn bump_attestation(env: Env, submission_id: BytesN<32>) -> Result<(), Error> {
+        if !Self::bump_one(&env, &submission_id) {
+            return Err(Error::AttestationNotFound);
+        }
+        ttl::extend_instance(&env);
+        Ok(())
+    }
+
+    /// `bump_attestation` for positions `start` to `start + limit` of
+    /// `alliance_id`'s list, plus the index pages holding them and the
+    /// alliance's validity period. `limit` is
+    /// clamped like `list_attestations`. Returns how many attestations were
+    /// extended; archived ones are skipped.
+    pub fn bump_alliance_attestations(
+        env: Env,
+        alliance_id: String,
+        start: u32,
+        limit: u32,
+    ) -> u32 {
+        validity::extend_period(&env, &ValidityScope::Alliance(alliance_id.clone()));
+        let scope = index::Scope::Alliance(alliance_id);
+        index::extend(&env, &scope, start, limit);
+        let mut bumped = 0;
+        for id in index::range(&env, &scope, start, limit).iter() {
+            if Self::bump_one(&env, &id) {
+                bumped += 1;
+            }
+        }
+        ttl::extend_instance(&env);
+        bumped
+    }
+
+    /// Extend the contract instance, every stored VK version and each
+    /// circuit's validity period and freshness window, so the contract
+    /// stays callable and historic attestations checkable even when no
+    /// proofs are verified for a while. Anyone may call this.
+    pub fn bump_contract(env: Env) {
+        ttl::extend_instance(&env);
+        for circuit in Self::circuits(env.clone()).iter() {
+            validity::extend_period(&env, &ValidityScope::Circuit(circuit.clone()));
+            validity::extend_freshness(&env, &circuit);
+            for version in 1..=Self::latest_version(&env, &circuit) {
+                let key = Self::key_vk(&circuit, version);
+                if env.storage().persistent().has(&key) {
+                    ttl::extend(&env, &key);
+                }
+            }
+        }
+    }
+
+    /// Extend a live attestation's entries; `false` if it is not live.
+    fn bump_one(env: &Env, submission_id: &BytesN<32>) -> bool {
+        let akey = Self::key_attestation(env, submission_id);
+        if !env.storage().persistent().has(&akey) {
+            return false;
+        }
+        ttl::extend(env, &akey);
+        ttl::mark_attested(env, submission_id);
+        dispute::extend(env, submission_id);
+        true
     }
 }
+
+#[cfg(test)]
+mod test;
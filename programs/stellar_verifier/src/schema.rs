@@ -0,0 +1,161 @@
+//! Typed decoding of circuit public inputs.
+//!
+//! Public inputs arrive as concatenated 32-byte big-endian BN254 field
+//! elements. Each registered circuit declares an ordered `FieldType` schema;
+//! `decode` walks the words against it and rejects any word that is not the
+//! canonical encoding of its declared type (non-zero high bytes, a bool other
+//! than 0/1, or a field element ≥ r).
+//!
+//! A `Commitment` word decodes like a raw `Field`; the contract additionally
+//! compares it against the submission commitment it recomputes from the
+//! call arguments, so a proof only attests the submission it was made for.
+//!
+//! A `Ledger` word is a `u32` naming the ledger the proof was generated
+//! for; circuits with a freshness window (see `validity`) must carry one,
+//! and the contract rejects proofs whose ledger is too old. Every circuit
+//! in `circuits/` ends with one.
+//!
+//! Circuits from `circuits/` are also known to the verifier crate's
+//! `public_inputs` module: registered under their id, their schema must be
+//! its layout, and `check_ranges` decodes their inputs with it to reject
+//! values the circuit asserts against (`min_days > max_days`, …).
+
+use crate::Error;
+use soroban_sdk::{contracttype, Bytes, BytesN, Env, Symbol, Vec};
+use ultrahonk_soroban_verifier::public_inputs::{PublicInputError, TypedCircuit, WordType, CIRCUITS};
+
+/// Declared type of one public-input word.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FieldType {
+    Bool,
+    U8,
+    U16,
+    U32,
+    /// Raw BN254 scalar; only checked to be `< r`.
+    Field,
+    /// Raw BN254 scalar that must equal the submission commitment.
+    Commitment,
+    /// `u32` ledger sequence the proof is anchored to; at most one per schema.
+    Ledger,
+}
+
+/// A decoded public-input word.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum OutputValue {
+    Bool(bool),
+    U8(u32),
+    U16(u32),
+    U32(u32),
+    Field(BytesN<32>),
+    Ledger(u32),
+}
+
+impl From<FieldType> for WordType {
+    fn from(t: FieldType) -> Self {
+        match t {
+            FieldType::Bool => WordType::Bool,
+            FieldType::U8 => WordType::U8,
+            FieldType::U16 => WordType::U16,
+            FieldType::U32 => WordType::U32,
+            FieldType::Ledger => WordType::Ledger,
+            FieldType::Field => WordType::Field,
+            FieldType::Commitment => WordType::Commitment,
+        }
+    }
+}
+
+impl From<PublicInputError> for Error {
+    fn from(e: PublicInputError) -> Self {
+        match e {
+            PublicInputError::Length { .. } => Error::PublicInputsLengthMismatch,
+            PublicInputError::NonCanonical { .. } => Error::NonCanonicalPublicInput,
+            PublicInputError::OutOfRange { .. } => Error::PublicInputOutOfRange,
+        }
+    }
+}
+
+impl FieldType {
+    fn decode_word(&self, env: &Env, word: &[u8; 32]) -> Result<OutputValue, Error> {
+        if !WordType::from(*self).is_canonical(word) {
+            return Err(Error::NonCanonicalPublicInput);
+        }
+        let low = u32::from_be_bytes([word[28], word[29], word[30], word[31]]);
+        Ok(match self {
+            FieldType::Bool => OutputValue::Bool(low == 1),
+            FieldType::U8 => OutputValue::U8(low),
+            FieldType::U16 => OutputValue::U16(low),
+            FieldType::U32 => OutputValue::U32(low),
+            FieldType::Ledger => OutputValue::Ledger(low),
+            FieldType::Field | FieldType::Commitment => {
+                OutputValue::Field(BytesN::from_array(env, word))
+            }
+        })
+    }
+}
+
+/// The typed definition of the circuit registered as `circuit`, if it is
+/// one of `circuits/`.
+pub fn typed_circuit(env: &Env, circuit: &Symbol) -> Option<&'static TypedCircuit> {
+    CIRCUITS
+        .iter()
+        .find(|c| *circuit == Symbol::new(env, c.id))
+}
+
+/// `true` iff `schema` is word for word the layout of `typed`.
+pub fn matches_layout(schema: &Vec<FieldType>, typed: &TypedCircuit) -> bool {
+    schema.len() as usize == typed.layout.len()
+        && schema
+            .iter()
+            .zip(typed.layout)
+            .all(|(ty, word)| WordType::from(ty) == *word)
+}
+
+/// The ledger a decoded proof is anchored to, if its schema has a `Ledger`
+/// word.
+pub fn anchor_ledger(outputs: &Vec<OutputValue>) -> Option<u32> {
+    outputs.iter().find_map(|v| match v {
+        OutputValue::Ledger(ledger) => Some(ledger),
+        _ => None,
+    })
+}
+
+/// Range-check `public_inputs` of `circuit` against its typed definition.
+///
+/// Circuits without one, and registrations made before their schema had to
+/// match it, are only checked by `decode`.
+pub fn check_ranges(
+    env: &Env,
+    circuit: &Symbol,
+    schema: &Vec<FieldType>,
+    public_inputs: &Bytes,
+) -> Result<(), Error> {
+    match typed_circuit(env, circuit) {
+        Some(typed) if matches_layout(schema, typed) => {
+            Ok((typed.check)(&public_inputs.to_alloc_vec())?)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Decode `public_inputs` against `schema`, one 32-byte word per entry.
+pub fn decode(
+    env: &Env,
+    schema: &Vec<FieldType>,
+    public_inputs: &Bytes,
+) -> Result<Vec<OutputValue>, Error> {
+    if public_inputs.len() != schema.len() * 32 {
+        return Err(Error::PublicInputsLengthMismatch);
+    }
+    let mut out = Vec::new(env);
+    for (i, ty) in schema.iter().enumerate() {
+        let start = i as u32 * 32;
+        let mut word = [0u8; 32];
+        public_inputs
+            .slice(start..start + 32)
+            .copy_into_slice(&mut word);
+        out.push_back(ty.decode_word(env, &word)?);
+    }
+    Ok(out)
+}
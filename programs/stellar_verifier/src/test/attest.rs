@@ -0,0 +1,61 @@
+use super::{attest, circuit, setup, submission};
+use crate::{Attested, Error};
+use soroban_sdk::{
+    testutils::{Address as _, Events as _},
+    Address, Bytes, Event, String,
+};
+
+#[test]
+fn recording_stores_and_emits_the_attestation() {
+    let s = setup();
+    let client = s.client();
+    let submitter = Address::generate(&s.env);
+    let id = submission(&s.env, 1);
+
+    let attestation = attest(&s, &id, "alliance", Some(&submitter));
+    assert_eq!(
+        s.env.events().all(),
+        [Attested {
+            alliance_id: attestation.alliance_id.clone(),
+            submission_id: id.clone(),
+            submitter: Some(submitter),
+            passed: attestation.passed,
+            outputs: attestation.outputs.clone(),
+            ledger: attestation.ledger,
+            timestamp: attestation.timestamp,
+            circuit: circuit(&s.env),
+            vk_version: 1,
+        }
+        .to_xdr(&s.env, &s.id)]
+    );
+    assert_eq!(client.get_attestation(&id), Some(attestation));
+}
+
+#[test]
+fn a_named_submitter_must_authorize() {
+    let s = setup();
+    let client = s.client();
+    let submitter = Address::generate(&s.env);
+    let alliance = String::from_str(&s.env, "alliance");
+    let id = submission(&s.env, 1);
+    let empty = Bytes::new(&s.env);
+    let call = |submitter: Option<Address>| {
+        client.try_verify_and_attest(
+            &circuit(&s.env),
+            &alliance,
+            &id,
+            &submitter,
+            &empty,
+            &empty,
+        )
+    };
+
+    // Without the submitter's signature the call fails before any check of
+    // its own; without a submitter it gets as far as the proof.
+    s.env.set_auths(&[]);
+    assert!(matches!(call(Some(submitter.clone())), Err(Err(_))));
+    assert_eq!(call(None), Err(Ok(Error::ProofParseError)));
+
+    s.env.mock_all_auths();
+    assert_eq!(call(Some(submitter)), Err(Ok(Error::ProofParseError)));
+}
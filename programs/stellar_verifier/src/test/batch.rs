@@ -0,0 +1,96 @@
+use super::{circuit, entry, prepared, setup, submission, Setup, PROOF};
+use crate::{Batch, BatchOutcome, Error, OptimizationAttestation, ATTEST_CPU_OVERHEAD};
+use soroban_sdk::{vec, Symbol};
+use ultrahonk_soroban_verifier::{cost::Phase, ec::pairing_check};
+
+/// What `verify_and_attest_batch` charges the first entry of `circuit`,
+/// and each later one.
+fn charges(s: &Setup) -> (u64, u64) {
+    let env = &s.env;
+    let estimate = env.as_contract(&s.id, || {
+        let active = OptimizationAttestation::active_version(env, &circuit(env)).unwrap();
+        let verifier = active.prepared.verifier(env, active.meta());
+        OptimizationAttestation::estimate(&verifier, PROOF.len() as u32).unwrap()
+    });
+    let full = estimate.cpu_insns() + ATTEST_CPU_OVERHEAD;
+    assert_eq!(
+        s.client()
+            .estimate_verify_cost(&circuit(env), &(PROOF.len() as u32))
+            .cpu_insns,
+        full
+    );
+    (
+        full + estimate.phase(Phase::Pairing).cpu_insns,
+        full - estimate.phase(Phase::VkLoad).cpu_insns,
+    )
+}
+
+#[test]
+fn entries_past_the_limit_are_skipped() {
+    let s = setup();
+    let client = s.client();
+    let (first, later) = charges(&s);
+    let entries = vec![
+        &s.env,
+        entry(&s, &submission(&s.env, 1)),
+        entry(&s, &submission(&s.env, 2)),
+        entry(&s, &submission(&s.env, 3)),
+    ];
+    // Every entry that is charged is then rejected by `prepare`.
+    let rejected = BatchOutcome::Rejected(Error::NonCanonicalPublicInput as u32);
+
+    assert_eq!(
+        client.verify_and_attest_batch(&entries, &(first + later)),
+        vec![&s.env, rejected.clone(), rejected.clone(), BatchOutcome::Skipped]
+    );
+    assert_eq!(
+        client.verify_and_attest_batch(&entries, &(first + later - 1)),
+        vec![&s.env, rejected.clone(), BatchOutcome::Skipped, BatchOutcome::Skipped]
+    );
+    assert_eq!(
+        client.verify_and_attest_batch(&entries, &(first - 1)),
+        vec![
+            &s.env,
+            BatchOutcome::Skipped,
+            BatchOutcome::Skipped,
+            BatchOutcome::Skipped
+        ]
+    );
+
+    // An unknown circuit is rejected without being charged.
+    let mut unknown = entry(&s, &submission(&s.env, 4));
+    unknown.circuit = Symbol::new(&s.env, "unknown");
+    let entries = vec![&s.env, unknown, entry(&s, &submission(&s.env, 5))];
+    assert_eq!(
+        client.verify_and_attest_batch(&entries, &first),
+        vec![
+            &s.env,
+            BatchOutcome::Rejected(Error::VkNotSet as u32),
+            rejected
+        ]
+    );
+}
+
+#[test]
+fn in_batch_duplicates_are_rejected() {
+    let s = setup();
+    let env = &s.env;
+    let id = submission(env, 1);
+    env.as_contract(&s.id, || {
+        let mut batch = Batch::new(u64::MAX);
+        assert_eq!(batch.charge(env, &entry(&s, &id)), Ok(true));
+        batch.stage(env, 0, entry(&s, &id), prepared(env)).unwrap();
+        assert_eq!(
+            batch.stage(env, 1, entry(&s, &id), prepared(env)),
+            Err(Error::AlreadyAttested)
+        );
+        batch
+            .stage(env, 2, entry(&s, &submission(env, 2)), prepared(env))
+            .unwrap();
+
+        let slots: alloc::vec::Vec<usize> = batch.pending.iter().map(|p| p.0).collect();
+        assert_eq!(slots, [0, 2]);
+        let (_, _, _, claim) = &batch.pending[0];
+        assert!(pairing_check(env, &claim.p0, &claim.p1));
+    });
+}
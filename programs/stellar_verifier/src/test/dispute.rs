@@ -0,0 +1,217 @@
+use super::{advance, attest, setup, submission, Setup};
+use crate::{
+    dispute::{DisputeOpened, DisputeResolved, DEFAULT_WINDOW_LEDGERS},
+    AttestationStatus, Dispute, DisputeResolution, DisputeRole, DisputeState, Error,
+};
+use soroban_sdk::{
+    testutils::{Address as _, Events as _},
+    Address, BytesN, Event,
+};
+
+struct Roles {
+    reporter: Address,
+    guardian: Address,
+}
+
+fn roles(s: &Setup) -> Roles {
+    let client = s.client();
+    let reporter = Address::generate(&s.env);
+    let guardian = Address::generate(&s.env);
+    client.set_dispute_role(&reporter, &Some(DisputeRole::Reporter));
+    client.set_dispute_role(&guardian, &Some(DisputeRole::Guardian));
+    Roles { reporter, guardian }
+}
+
+fn evidence(s: &Setup) -> BytesN<32> {
+    BytesN::from_array(&s.env, &[0xee; 32])
+}
+
+#[test]
+fn open_and_resolve() {
+    let s = setup();
+    let client = s.client();
+    let r = roles(&s);
+    let id = submission(&s.env, 1);
+    attest(&s, &id, "alliance", None);
+    assert_eq!(client.attestation_status(&id), Some(AttestationStatus::Undisputed));
+
+    advance(&s.env, 10);
+    let opened = client.open_dispute(&r.reporter, &id, &evidence(&s));
+    let expected = Dispute {
+        submission_id: id.clone(),
+        reporter: r.reporter.clone(),
+        evidence: evidence(&s),
+        opened_at: super::START + 10,
+        state: DisputeState::Open,
+        resolved_by: None,
+        resolved_at: None,
+    };
+    assert_eq!(opened, expected);
+    assert_eq!(
+        s.env.events().all(),
+        [DisputeOpened {
+            submission_id: id.clone(),
+            reporter: r.reporter.clone(),
+            evidence: evidence(&s),
+            opened_at: super::START + 10,
+        }
+        .to_xdr(&s.env, &s.id)]
+    );
+    assert_eq!(s.env.auths()[0].0, r.reporter);
+    assert_eq!(client.get_dispute(&id), Some(expected.clone()));
+    assert_eq!(client.attestation_status(&id), Some(AttestationStatus::Disputed));
+
+    advance(&s.env, 5);
+    let resolved = client.resolve_dispute(&r.guardian, &id, &DisputeResolution::Revoked);
+    let expected = Dispute {
+        state: DisputeState::Resolved(DisputeResolution::Revoked),
+        resolved_by: Some(r.guardian.clone()),
+        resolved_at: Some(super::START + 15),
+        ..expected
+    };
+    assert_eq!(resolved, expected);
+    assert_eq!(
+        s.env.events().all(),
+        [DisputeResolved {
+            submission_id: id.clone(),
+            guardian: r.guardian.clone(),
+            resolution: DisputeResolution::Revoked,
+            resolved_at: super::START + 15,
+        }
+        .to_xdr(&s.env, &s.id)]
+    );
+    assert_eq!(s.env.auths()[0].0, r.guardian);
+    assert_eq!(client.get_dispute(&id), Some(expected));
+    assert_eq!(client.attestation_status(&id), Some(AttestationStatus::Revoked));
+
+    // The attestation itself is left as it was recorded.
+    assert!(client.has_attestation(&id));
+}
+
+#[test]
+fn upheld_dispute_reads_as_upheld() {
+    let s = setup();
+    let client = s.client();
+    let r = roles(&s);
+    let id = submission(&s.env, 1);
+    attest(&s, &id, "alliance", None);
+
+    client.open_dispute(&r.guardian, &id, &evidence(&s));
+    client.resolve_dispute(&r.guardian, &id, &DisputeResolution::Upheld);
+    assert_eq!(client.attestation_status(&id), Some(AttestationStatus::Upheld));
+    assert_eq!(
+        client.try_resolve_dispute(&r.guardian, &id, &DisputeResolution::Revoked),
+        Err(Ok(Error::DisputeAlreadyResolved))
+    );
+}
+
+#[test]
+fn roles_are_granted_by_the_admin() {
+    let s = setup();
+    let client = s.client();
+    let account = Address::generate(&s.env);
+
+    client.set_dispute_role(&account, &Some(DisputeRole::Reporter));
+    assert_eq!(s.env.auths()[0].0, s.admin);
+    assert_eq!(client.dispute_role(&account), Some(DisputeRole::Reporter));
+
+    client.set_dispute_role(&account, &None);
+    assert_eq!(client.dispute_role(&account), None);
+}
+
+#[test]
+fn only_roles_open_and_only_guardians_resolve() {
+    let s = setup();
+    let client = s.client();
+    let r = roles(&s);
+    let stranger = Address::generate(&s.env);
+    let id = submission(&s.env, 1);
+    attest(&s, &id, "alliance", None);
+
+    assert_eq!(
+        client.try_open_dispute(&stranger, &id, &evidence(&s)),
+        Err(Ok(Error::NotDisputeRole))
+    );
+    client.open_dispute(&r.reporter, &id, &evidence(&s));
+    assert_eq!(
+        client.try_resolve_dispute(&r.reporter, &id, &DisputeResolution::Revoked),
+        Err(Ok(Error::NotDisputeRole))
+    );
+    assert_eq!(
+        client.try_resolve_dispute(&stranger, &id, &DisputeResolution::Revoked),
+        Err(Ok(Error::NotDisputeRole))
+    );
+
+    // A revoked role no longer opens disputes.
+    client.set_dispute_role(&r.reporter, &None);
+    let other = submission(&s.env, 2);
+    attest(&s, &other, "alliance", None);
+    assert_eq!(
+        client.try_open_dispute(&r.reporter, &other, &evidence(&s)),
+        Err(Ok(Error::NotDisputeRole))
+    );
+}
+
+#[test]
+fn window_runs_from_the_attestation_ledger() {
+    let s = setup();
+    let client = s.client();
+    let r = roles(&s);
+    assert_eq!(client.dispute_window(), DEFAULT_WINDOW_LEDGERS);
+    client.set_dispute_window(&50);
+    assert_eq!(client.dispute_window(), 50);
+
+    advance(&s.env, 20);
+    let last_day = submission(&s.env, 1);
+    let closed = submission(&s.env, 2);
+    let attested_at = attest(&s, &last_day, "alliance", None).ledger;
+    attest(&s, &closed, "alliance", None);
+
+    // The window is counted from the attestation, not from deployment.
+    advance(&s.env, 50);
+    assert_eq!(s.env.ledger().sequence(), attested_at + 50);
+    client.open_dispute(&r.reporter, &last_day, &evidence(&s));
+
+    advance(&s.env, 1);
+    assert_eq!(
+        client.try_open_dispute(&r.reporter, &closed, &evidence(&s)),
+        Err(Ok(Error::DisputeWindowClosed))
+    );
+
+    // Widening the window reopens it for existing attestations.
+    client.set_dispute_window(&51);
+    client.open_dispute(&r.reporter, &closed, &evidence(&s));
+}
+
+#[test]
+fn one_dispute_per_attestation() {
+    let s = setup();
+    let client = s.client();
+    let r = roles(&s);
+    let id = submission(&s.env, 1);
+    attest(&s, &id, "alliance", None);
+
+    client.open_dispute(&r.reporter, &id, &evidence(&s));
+    assert_eq!(
+        client.try_open_dispute(&r.guardian, &id, &evidence(&s)),
+        Err(Ok(Error::DisputeExists))
+    );
+}
+
+#[test]
+fn unknown_ids_are_rejected() {
+    let s = setup();
+    let client = s.client();
+    let r = roles(&s);
+    let id = submission(&s.env, 1);
+
+    assert_eq!(client.attestation_status(&id), None);
+    assert_eq!(
+        client.try_open_dispute(&r.reporter, &id, &evidence(&s)),
+        Err(Ok(Error::AttestationNotFound))
+    );
+    assert_eq!(
+        client.try_resolve_dispute(&r.guardian, &id, &DisputeResolution::Upheld),
+        Err(Ok(Error::DisputeNotFound))
+    );
+}
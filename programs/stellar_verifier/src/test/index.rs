@@ -0,0 +1,106 @@
+use super::{attest, setup, submission, Setup};
+use crate::index::{Scope, MAX_LIST_LIMIT, PAGE_SIZE};
+use soroban_sdk::{symbol_short, testutils::Address as _, Address, BytesN, String, Vec};
+
+/// The ids stored on page `page` of `scope`.
+fn page(s: &Setup, scope: &Scope, page: u32) -> Option<Vec<BytesN<32>>> {
+    s.env.as_contract(&s.id, || {
+        s.env
+            .storage()
+            .persistent()
+            .get(&(symbol_short!("idx_page"), scope.clone(), page))
+    })
+}
+
+fn ids(list: &Vec<crate::Attestation>) -> alloc::vec::Vec<BytesN<32>> {
+    list.iter().map(|a| a.submission_id).collect()
+}
+
+#[test]
+fn pages_roll_over_at_page_size() {
+    let s = setup();
+    let client = s.client();
+    let alliance = String::from_str(&s.env, "alliance");
+    let submitter = Address::generate(&s.env);
+    for n in 0..=PAGE_SIZE {
+        attest(&s, &submission(&s.env, n), "alliance", Some(&submitter));
+    }
+
+    for scope in [
+        Scope::Global,
+        Scope::Alliance(alliance.clone()),
+        Scope::Submitter(submitter.clone()),
+    ] {
+        let first = page(&s, &scope, 0).unwrap();
+        assert_eq!(first.len(), PAGE_SIZE);
+        assert_eq!(first.get(PAGE_SIZE - 1), Some(submission(&s.env, PAGE_SIZE - 1)));
+        let second = page(&s, &scope, 1).unwrap();
+        assert_eq!(second.len(), 1);
+        assert_eq!(second.get(0), Some(submission(&s.env, PAGE_SIZE)));
+        assert_eq!(page(&s, &scope, 2), None);
+    }
+    assert_eq!(client.total_attestations(), PAGE_SIZE + 1);
+    assert_eq!(client.attestation_count(&alliance), PAGE_SIZE + 1);
+    assert_eq!(client.submitter_attestation_count(&submitter), PAGE_SIZE + 1);
+
+    // A list across the page boundary reads from both pages in order.
+    let across = client.list_attestations(&alliance, &(PAGE_SIZE - 2), &4);
+    let expected: alloc::vec::Vec<_> = (PAGE_SIZE - 2..=PAGE_SIZE)
+        .map(|n| submission(&s.env, n))
+        .collect();
+    assert_eq!(ids(&across), expected);
+    assert_eq!(
+        ids(&client.list_attestations_by_submitter(&submitter, &(PAGE_SIZE - 2), &4)),
+        expected
+    );
+    assert_eq!(
+        ids(&client.list_all_attestations(&(PAGE_SIZE - 2), &4)),
+        expected
+    );
+}
+
+#[test]
+fn lists_are_capped_at_max_list_limit() {
+    let s = setup();
+    let client = s.client();
+    let alliance = String::from_str(&s.env, "alliance");
+    for n in 0..=PAGE_SIZE {
+        attest(&s, &submission(&s.env, n), "alliance", None);
+    }
+
+    let list = client.list_attestations(&alliance, &0, &u32::MAX);
+    assert_eq!(list.len(), MAX_LIST_LIMIT);
+    let expected: alloc::vec::Vec<_> = (0..MAX_LIST_LIMIT).map(|n| submission(&s.env, n)).collect();
+    assert_eq!(ids(&list), expected);
+    assert_eq!(client.list_all_attestations(&0, &(MAX_LIST_LIMIT + 1)).len(), MAX_LIST_LIMIT);
+
+    // The last page holds what is left, and reading past the end is empty.
+    let rest = client.list_attestations(&alliance, &MAX_LIST_LIMIT, &MAX_LIST_LIMIT);
+    assert_eq!(rest.len(), PAGE_SIZE + 1 - MAX_LIST_LIMIT);
+    assert!(client
+        .list_attestations(&alliance, &(PAGE_SIZE + 1), &MAX_LIST_LIMIT)
+        .is_empty());
+    assert!(client.list_attestations(&alliance, &u32::MAX, &u32::MAX).is_empty());
+}
+
+#[test]
+fn lists_are_kept_per_alliance_and_submitter() {
+    let s = setup();
+    let client = s.client();
+    let submitter = Address::generate(&s.env);
+    attest(&s, &submission(&s.env, 1), "a", Some(&submitter));
+    attest(&s, &submission(&s.env, 2), "b", None);
+    attest(&s, &submission(&s.env, 3), "a", None);
+
+    let a = String::from_str(&s.env, "a");
+    assert_eq!(
+        ids(&client.list_attestations(&a, &0, &10)),
+        [submission(&s.env, 1), submission(&s.env, 3)]
+    );
+    assert_eq!(
+        ids(&client.list_attestations_by_submitter(&submitter, &0, &10)),
+        [submission(&s.env, 1)]
+    );
+    assert_eq!(client.total_attestations(), 3);
+    assert_eq!(client.attestation_count(&String::from_str(&s.env, "c")), 0);
+}
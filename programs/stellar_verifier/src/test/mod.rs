@@ -0,0 +1,140 @@
+//! Contract tests against the soroban test environment.
+//!
+//! Each module covers one feature through the generated client, with real
+//! storage and events. Attestations are written through `record`, the path
+//! `verify_and_attest` takes once a proof has verified, so the tests do not
+//! depend on a proof bound to each submission id.
+
+use crate::{
+    Attestation, BatchEntry, CircuitConfig, FieldType, OptimizationAttestation, OptimizationAttestationClient,
+    OutputValue, Prepared, ProofBbVersion, ProofFlavor, ProofOracleHash,
+};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Bytes, BytesN, Env, Map, String, Symbol,
+};
+
+mod attest;
+mod batch;
+mod dispute;
+mod index;
+mod rotation;
+mod session;
+mod verify;
+mod ttl;
+mod validity;
+
+/// The browser prover's VK, proof and public inputs, from `api/stellar/`.
+/// The public inputs do not decode against the test schema.
+const VK: &[u8] = include_bytes!("../../../../api/stellar/vk.bin");
+const PROOF: &[u8] = include_bytes!("../../../../api/stellar/proof.bin");
+const PUBLIC_INPUTS: &[u8] = include_bytes!("../../../../api/stellar/public_inputs.bin");
+
+/// Ledger the tests start at.
+const START: u32 = 1_000;
+
+/// VK rotation delay the contract is deployed with.
+const DELAY: u32 = 100;
+
+pub struct Setup {
+    pub env: Env,
+    pub id: Address,
+    pub admin: Address,
+}
+
+impl Setup {
+    pub fn client(&self) -> OptimizationAttestationClient<'_> {
+        OptimizationAttestationClient::new(&self.env, &self.id)
+    }
+}
+
+pub fn circuit(env: &Env) -> Symbol {
+    Symbol::new(env, "test_circuit")
+}
+
+pub fn config(env: &Env) -> CircuitConfig {
+    CircuitConfig {
+        vk: Bytes::from_slice(env, VK),
+        flavor: ProofFlavor::Ultra,
+        oracle_hash: ProofOracleHash::Keccak,
+        bb_version: ProofBbVersion::V0_87_0,
+        schema: vec![env, FieldType::Commitment, FieldType::Bool],
+    }
+}
+
+/// Deploy the contract with `circuit` registered, at ledger `START`.
+pub fn setup() -> Setup {
+    let env = Env::default();
+    env.ledger().set_protocol_version(26);
+    env.ledger().set_sequence_number(START);
+    env.cost_estimate().budget().reset_unlimited();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let mut circuits = Map::new(&env);
+    circuits.set(circuit(&env), config(&env));
+    let id = env.register(OptimizationAttestation, (admin.clone(), DELAY, circuits));
+    Setup { env, id, admin }
+}
+
+/// A submission id derived from `n`.
+pub fn submission(env: &Env, n: u32) -> BytesN<32> {
+    let mut id = [0u8; 32];
+    id[28..].copy_from_slice(&n.to_be_bytes());
+    BytesN::from_array(env, &id)
+}
+
+/// A batch or session entry for `submission_id` with the `api/stellar/`
+/// proof.
+pub fn entry(s: &Setup, submission_id: &BytesN<32>) -> BatchEntry {
+    BatchEntry {
+        circuit: circuit(&s.env),
+        alliance_id: String::from_str(&s.env, "alliance"),
+        submission_id: submission_id.clone(),
+        submitter: None,
+        public_inputs: Bytes::from_slice(&s.env, PUBLIC_INPUTS),
+        proof: Bytes::from_slice(&s.env, PROOF),
+    }
+}
+
+/// Record a passing attestation of `circuit` at the current ledger.
+pub fn attest(
+    s: &Setup,
+    submission_id: &BytesN<32>,
+    alliance_id: &str,
+    submitter: Option<&Address>,
+) -> Attestation {
+    let env = &s.env;
+    env.as_contract(&s.id, || {
+        OptimizationAttestation::record(
+            env,
+            circuit(env),
+            String::from_str(env, alliance_id),
+            submission_id.clone(),
+            submitter.cloned(),
+            prepared(env),
+        )
+    })
+}
+
+/// What `prepare` returns for a passing proof of `circuit`. Must be called
+/// as the contract.
+pub fn prepared(env: &Env) -> Prepared {
+    let active = OptimizationAttestation::active_version(env, &circuit(env)).unwrap();
+    Prepared {
+        vk: active.prepared.clone(),
+        meta: active.meta(),
+        vk_version: active.version,
+        outputs: vec![
+            env,
+            OutputValue::Field(BytesN::from_array(env, &[7; 32])),
+            OutputValue::Bool(true),
+        ],
+        passed: true,
+    }
+}
+
+pub fn advance(env: &Env, ledgers: u32) {
+    let now = env.ledger().sequence();
+    env.ledger().set_sequence_number(now + ledgers);
+}
@@ -0,0 +1,127 @@
+use super::{advance, attest, circuit, config, setup, submission, DELAY, START};
+use crate::{CircuitConfig, Error, FieldType, VkCancelled, VkProposed};
+use soroban_sdk::{testutils::Events as _, vec, Env, Event, Symbol};
+
+/// `config` with its schema words swapped, so the versions can be told apart.
+fn rotated(env: &Env) -> CircuitConfig {
+    CircuitConfig {
+        schema: vec![env, FieldType::Bool, FieldType::Commitment],
+        ..config(env)
+    }
+}
+
+#[test]
+fn proposals_activate_after_the_delay() {
+    let s = setup();
+    let client = s.client();
+    let c = circuit(&s.env);
+    assert_eq!(client.activation_delay(), DELAY);
+
+    assert_eq!(client.propose_vk(&c, &rotated(&s.env)), 2);
+    assert_eq!(s.env.auths()[0].0, s.admin);
+    assert_eq!(
+        s.env.events().all(),
+        [VkProposed {
+            circuit: c.clone(),
+            version: 2,
+            activates_at: START + DELAY,
+        }
+        .to_xdr(&s.env, &s.id)]
+    );
+    let pending = client.pending_vk(&c).unwrap();
+    assert_eq!(pending.version, 2);
+    assert_eq!(pending.activates_at, START + DELAY);
+    assert_eq!(pending.schema, rotated(&s.env).schema);
+
+    // Until the delay has passed, attestations use version 1.
+    advance(&s.env, DELAY - 1);
+    assert_eq!(client.vk_version(&c).version, 1);
+    assert_eq!(client.output_schema(&c), config(&s.env).schema);
+    assert_eq!(attest(&s, &submission(&s.env, 1), "alliance", None).vk_version, 1);
+
+    advance(&s.env, 1);
+    assert_eq!(client.vk_version(&c).version, 2);
+    assert_eq!(client.output_schema(&c), rotated(&s.env).schema);
+    assert_eq!(client.pending_vk(&c), None);
+    assert_eq!(attest(&s, &submission(&s.env, 2), "alliance", None).vk_version, 2);
+
+    // Superseded versions stay readable for old attestations.
+    assert_eq!(client.vk_version_at(&c, &1).schema, config(&s.env).schema);
+}
+
+#[test]
+fn one_proposal_may_be_pending() {
+    let s = setup();
+    let client = s.client();
+    let c = circuit(&s.env);
+
+    client.propose_vk(&c, &rotated(&s.env));
+    assert_eq!(
+        client.try_propose_vk(&c, &config(&s.env)),
+        Err(Ok(Error::VkRotationPending))
+    );
+
+    // Once it has activated, the next one may be proposed.
+    advance(&s.env, DELAY);
+    assert_eq!(client.propose_vk(&c, &config(&s.env)), 3);
+}
+
+#[test]
+fn cancel_withdraws_the_pending_proposal() {
+    let s = setup();
+    let client = s.client();
+    let c = circuit(&s.env);
+    assert_eq!(client.try_cancel_vk(&c), Err(Ok(Error::NoPendingVk)));
+
+    client.propose_vk(&c, &rotated(&s.env));
+    client.cancel_vk(&c);
+    assert_eq!(s.env.auths()[0].0, s.admin);
+    assert_eq!(
+        s.env.events().all(),
+        [VkCancelled {
+            circuit: c.clone(),
+            version: 2,
+        }
+        .to_xdr(&s.env, &s.id)]
+    );
+    assert_eq!(client.pending_vk(&c), None);
+    assert_eq!(client.try_vk_version_at(&c, &2), Err(Ok(Error::VkNotSet)));
+    assert_eq!(client.try_cancel_vk(&c), Err(Ok(Error::NoPendingVk)));
+
+    // The cancelled proposal never activates, and its number is reused.
+    advance(&s.env, DELAY);
+    assert_eq!(client.vk_version(&c).version, 1);
+    assert_eq!(client.propose_vk(&c, &rotated(&s.env)), 2);
+}
+
+#[test]
+fn new_circuits_wait_for_the_delay_too() {
+    let s = setup();
+    let client = s.client();
+    let other = Symbol::new(&s.env, "other_circuit");
+
+    assert_eq!(client.propose_vk(&other, &config(&s.env)), 1);
+    assert_eq!(client.circuits(), vec![&s.env, circuit(&s.env), other.clone()]);
+    assert_eq!(client.try_vk_version(&other), Err(Ok(Error::VkNotSet)));
+
+    // Cancelling a new circuit's first version unregisters it.
+    client.cancel_vk(&other);
+    assert_eq!(client.circuits(), vec![&s.env, circuit(&s.env)]);
+
+    client.propose_vk(&other, &config(&s.env));
+    advance(&s.env, DELAY);
+    assert_eq!(client.vk_version(&other).version, 1);
+}
+
+#[test]
+fn proposals_are_validated() {
+    let s = setup();
+    let client = s.client();
+    let c = circuit(&s.env);
+    let bad = CircuitConfig {
+        schema: vec![&s.env, FieldType::Bool, FieldType::Bool],
+        ..config(&s.env)
+    };
+    assert_eq!(client.try_propose_vk(&c, &bad), Err(Ok(Error::InvalidSchema)));
+    assert_eq!(client.pending_vk(&c), None);
+}
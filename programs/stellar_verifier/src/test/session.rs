@@ -0,0 +1,132 @@
+use super::{circuit, entry, setup, submission, Setup, PROOF, PUBLIC_INPUTS, START};
+use crate::{
+    Error, OptimizationAttestation, SessionOutcome, SessionStage, VerifySession,
+    SESSION_TTL_LEDGERS,
+};
+use soroban_sdk::{testutils::Address as _, vec, Address, Bytes, BytesN, String};
+use ultrahonk_soroban_verifier::VerificationSession;
+
+/// Store the session `open_session` would store for the `api/stellar/`
+/// proof, which `open_session` itself rejects (`NonCanonicalPublicInput`).
+fn open(s: &Setup, caller: &Address, submission_id: &BytesN<32>) {
+    let env = &s.env;
+    env.as_contract(&s.id, || {
+        let active = OptimizationAttestation::active_version(env, &circuit(env)).unwrap();
+        let state = VerificationSession::new(env, PROOF, PUBLIC_INPUTS);
+        let session = VerifySession {
+            caller: caller.clone(),
+            circuit: circuit(env),
+            vk_version: active.version,
+            alliance_id: String::from_str(env, "alliance"),
+            submitter: None,
+            outputs: vec![env],
+            passed: true,
+            expires_at: env.ledger().sequence() + SESSION_TTL_LEDGERS,
+            stage: SessionStage::Transcript,
+            state: Bytes::from_slice(env, &state.to_bytes()),
+        };
+        OptimizationAttestation::store_session(env, submission_id, &session);
+    });
+}
+
+#[test]
+fn a_session_does_not_hold_the_submission_against_other_callers() {
+    let s = setup();
+    let client = s.client();
+    let first = Address::generate(&s.env);
+    let second = Address::generate(&s.env);
+    let id = submission(&s.env, 1);
+    open(&s, &first, &id);
+
+    assert_eq!(
+        client.try_open_session(&first, &entry(&s, &id)),
+        Err(Ok(Error::SessionInProgress))
+    );
+    // Another caller gets past the session check to the usual ones.
+    assert_eq!(
+        client.try_open_session(&second, &entry(&s, &id)),
+        Err(Ok(Error::NonCanonicalPublicInput))
+    );
+    assert_eq!(client.get_session(&second, &id), None);
+
+    // Nor can they advance or cancel the first caller's session.
+    let pis = Bytes::from_slice(&s.env, PUBLIC_INPUTS);
+    let proof = Bytes::from_slice(&s.env, PROOF);
+    assert_eq!(
+        client.try_advance_session(&second, &id, &pis, &proof, &1),
+        Err(Ok(Error::SessionNotFound))
+    );
+    assert_eq!(
+        client.try_cancel_session(&second, &id),
+        Err(Ok(Error::SessionNotFound))
+    );
+    assert_eq!(
+        client.get_session(&first, &id).map(|s| s.stage),
+        Some(SessionStage::Transcript)
+    );
+    assert_eq!(
+        client.try_estimate_session_step_cost(&second, &id, &(PROOF.len() as u32), &1),
+        Err(Ok(Error::SessionNotFound))
+    );
+
+    client.cancel_session(&first, &id);
+    assert_eq!(s.env.auths()[0].0, first);
+    assert_eq!(client.get_session(&first, &id), None);
+}
+
+#[test]
+fn steps_stay_within_their_estimate_and_attest() {
+    let s = setup();
+    let client = s.client();
+    let caller = Address::generate(&s.env);
+    let id = submission(&s.env, 1);
+    open(&s, &caller, &id);
+
+    let pis = Bytes::from_slice(&s.env, PUBLIC_INPUTS);
+    let proof = Bytes::from_slice(&s.env, PROOF);
+    let mut steps = 0;
+    let attestation = loop {
+        let estimate =
+            client.estimate_session_step_cost(&caller, &id, &(PROOF.len() as u32), &4);
+        let mut budget = s.env.cost_estimate().budget();
+        budget.reset_unlimited();
+        let outcome = client.advance_session(&caller, &id, &pis, &proof, &4);
+        assert!(budget.cpu_instruction_cost() <= estimate.cpu_insns);
+        assert!(budget.memory_bytes_cost() <= estimate.mem_bytes);
+        steps += 1;
+        match outcome {
+            SessionOutcome::Pending(stage) => {
+                assert_eq!(client.get_session(&caller, &id).unwrap().stage, stage);
+            }
+            SessionOutcome::Attested(attestation) => break attestation,
+        }
+    };
+    // Transcript, the 12 sumcheck rounds four at a time, relations,
+    // Shplemini, pairing.
+    assert_eq!(steps, 1 + 3 + 3);
+    assert!(attestation.passed);
+    assert_eq!(attestation.ledger, START);
+    assert_eq!(client.get_attestation(&id), Some(attestation));
+    assert_eq!(client.get_session(&caller, &id), None);
+}
+
+#[test]
+fn a_session_refuses_another_proof() {
+    let s = setup();
+    let client = s.client();
+    let caller = Address::generate(&s.env);
+    let id = submission(&s.env, 1);
+    open(&s, &caller, &id);
+
+    let pis = Bytes::from_slice(&s.env, PUBLIC_INPUTS);
+    let mut other = PROOF.to_vec();
+    other[100] ^= 1;
+    assert_eq!(
+        client.try_advance_session(&caller, &id, &pis, &Bytes::from_slice(&s.env, &other), &1),
+        Err(Ok(Error::SessionProofMismatch))
+    );
+    assert_eq!(
+        client.get_session(&caller, &id).map(|s| s.stage),
+        Some(SessionStage::Transcript)
+    );
+}
@@ -0,0 +1,143 @@
+use super::{attest, circuit, setup, submission, Setup};
+use crate::{ttl, AttestationPresence, DisputeRole, Error, OptimizationAttestation};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{storage::Persistent as _, Address as _, Ledger as _},
+    Address, Bytes, BytesN, Env, IntoVal, String, Val,
+};
+
+/// Remaining TTL of the persistent entry at `key`.
+fn ttl_of<K: IntoVal<Env, Val>>(s: &Setup, key: &K) -> u32 {
+    s.env.as_contract(&s.id, || s.env.storage().persistent().get_ttl(key))
+}
+
+fn attestation_ttl(s: &Setup, submission_id: &BytesN<32>) -> u32 {
+    let key = s.env.as_contract(&s.id, || {
+        OptimizationAttestation::key_attestation(&s.env, submission_id)
+    });
+    ttl_of(s, &key)
+}
+
+/// Drop the attestation entry of `submission_id` the way archival does,
+/// leaving its marker behind. The test environment restores archived
+/// entries on access, so archival cannot be reached by advancing the ledger.
+fn archive(s: &Setup, submission_id: &BytesN<32>) {
+    s.env.as_contract(&s.id, || {
+        s.env
+            .storage()
+            .persistent()
+            .remove(&OptimizationAttestation::key_attestation(&s.env, submission_id));
+    });
+}
+
+fn advance_to_threshold(s: &Setup) {
+    let now = s.env.ledger().sequence();
+    s.env
+        .ledger()
+        .set_sequence_number(now + ttl::EXTEND_TO - ttl::THRESHOLD + 1);
+}
+
+#[test]
+fn recording_extends_attestation_and_marker() {
+    let s = setup();
+    let id = submission(&s.env, 1);
+    attest(&s, &id, "alliance", None);
+
+    let max = s.env.as_contract(&s.id, || s.env.storage().max_ttl());
+    let attestation = attestation_ttl(&s, &id);
+    let marker = ttl_of(&s, &(symbol_short!("attested"), id.clone()));
+    assert_eq!(attestation, ttl::EXTEND_TO);
+    assert_eq!(marker, max);
+}
+
+#[test]
+fn bump_attestation_extends_attestation_and_dispute() {
+    let s = setup();
+    let client = s.client();
+    let reporter = Address::generate(&s.env);
+    client.set_dispute_role(&reporter, &Some(DisputeRole::Reporter));
+    let id = submission(&s.env, 1);
+    attest(&s, &id, "alliance", None);
+    client.open_dispute(&reporter, &id, &BytesN::from_array(&s.env, &[1; 32]));
+
+    let dispute_key = (symbol_short!("dispute"), id.clone());
+
+    advance_to_threshold(&s);
+    assert!(attestation_ttl(&s, &id) < ttl::THRESHOLD);
+    assert!(ttl_of(&s, &dispute_key) < ttl::THRESHOLD);
+
+    client.bump_attestation(&id);
+    assert_eq!(attestation_ttl(&s, &id), ttl::EXTEND_TO);
+    assert_eq!(ttl_of(&s, &dispute_key), ttl::EXTEND_TO);
+
+    assert_eq!(
+        client.try_bump_attestation(&submission(&s.env, 2)),
+        Err(Ok(Error::AttestationNotFound))
+    );
+}
+
+#[test]
+fn bump_alliance_attestations_pages_through_the_alliance() {
+    let s = setup();
+    let client = s.client();
+    for n in 0..5 {
+        attest(&s, &submission(&s.env, n), "alliance", None);
+    }
+    attest(&s, &submission(&s.env, 9), "other", None);
+
+    advance_to_threshold(&s);
+    let alliance = String::from_str(&s.env, "alliance");
+    assert_eq!(client.bump_alliance_attestations(&alliance, &1, &3), 3);
+    let bumped: [u32; 6] = [0, 1, 2, 3, 4, 9].map(|n| attestation_ttl(&s, &submission(&s.env, n)));
+    assert!(bumped[0] < ttl::THRESHOLD);
+    assert_eq!(bumped[1..4], [ttl::EXTEND_TO; 3]);
+    assert!(bumped[4] < ttl::THRESHOLD);
+    assert!(bumped[5] < ttl::THRESHOLD);
+}
+
+#[test]
+fn presence_tells_archived_from_missing() {
+    let s = setup();
+    let client = s.client();
+    let id = submission(&s.env, 1);
+    assert_eq!(client.attestation_presence(&id), AttestationPresence::Missing);
+
+    attest(&s, &id, "alliance", None);
+    attest(&s, &submission(&s.env, 2), "alliance", None);
+    assert_eq!(client.attestation_presence(&id), AttestationPresence::Live);
+
+    archive(&s, &id);
+    assert_eq!(client.attestation_presence(&id), AttestationPresence::Archived);
+    assert!(!client.has_attestation(&id));
+    assert_eq!(client.get_attestation(&id), None);
+
+    // Archived attestations cannot be bumped and are skipped by the
+    // alliance-wide bump.
+    assert_eq!(
+        client.try_bump_attestation(&id),
+        Err(Ok(Error::AttestationNotFound))
+    );
+    let alliance = String::from_str(&s.env, "alliance");
+    assert_eq!(client.bump_alliance_attestations(&alliance, &0, &10), 1);
+}
+
+#[test]
+fn archived_attestations_cannot_be_attested_again() {
+    let s = setup();
+    let client = s.client();
+    let id = submission(&s.env, 1);
+    attest(&s, &id, "alliance", None);
+    archive(&s, &id);
+
+    assert_eq!(
+        client.try_verify_and_attest(
+            &circuit(&s.env),
+            &String::from_str(&s.env, "alliance"),
+            &id,
+            &None,
+            &Bytes::new(&s.env),
+            &Bytes::new(&s.env),
+        ),
+        Err(Ok(Error::AlreadyAttested))
+    );
+}
@@ -0,0 +1,108 @@
+use super::{advance, attest, circuit, setup, submission};
+use crate::{ttl, DisputeResolution, DisputeRole, ValidityScope};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{storage::Persistent as _, Address as _},
+    Address, BytesN, String,
+};
+
+#[test]
+fn attestations_without_a_period_do_not_expire() {
+    let s = setup();
+    let client = s.client();
+    let id = submission(&s.env, 1);
+    assert!(!client.is_valid(&id));
+
+    let attestation = attest(&s, &id, "alliance", None);
+    assert_eq!(attestation.valid_until, None);
+    advance(&s.env, 400_000);
+    assert!(client.is_valid(&id));
+}
+
+#[test]
+fn valid_until_is_fixed_when_recorded() {
+    let s = setup();
+    let client = s.client();
+    let scope = ValidityScope::Circuit(circuit(&s.env));
+    client.set_validity_period(&scope, &Some(100));
+    assert_eq!(client.validity_period(&scope), Some(100));
+
+    let id = submission(&s.env, 1);
+    let attestation = attest(&s, &id, "alliance", None);
+    assert_eq!(attestation.valid_until, Some(super::START + 100));
+    assert_eq!(client.get_attestation(&id), Some(attestation));
+
+    // Changing the period later leaves recorded attestations alone.
+    client.set_validity_period(&scope, &None);
+    assert_eq!(client.validity_period(&scope), None);
+
+    advance(&s.env, 100);
+    assert!(client.is_valid(&id));
+    advance(&s.env, 1);
+    assert!(!client.is_valid(&id));
+    // An expired attestation is still stored.
+    assert!(client.has_attestation(&id));
+}
+
+#[test]
+fn shorter_period_wins() {
+    let s = setup();
+    let client = s.client();
+    let alliance = String::from_str(&s.env, "alliance");
+    client.set_validity_period(&ValidityScope::Circuit(circuit(&s.env)), &Some(100));
+    client.set_validity_period(&ValidityScope::Alliance(alliance), &Some(40));
+
+    let short = attest(&s, &submission(&s.env, 1), "alliance", None);
+    assert_eq!(short.valid_until, Some(super::START + 40));
+    let other = attest(&s, &submission(&s.env, 2), "other", None);
+    assert_eq!(other.valid_until, Some(super::START + 100));
+}
+
+#[test]
+fn periods_are_persistent_and_extended() {
+    let s = setup();
+    let client = s.client();
+    let scope = ValidityScope::Circuit(circuit(&s.env));
+    client.set_validity_period(&scope, &Some(100));
+
+    let ttl_of_period = || {
+        s.env.as_contract(&s.id, || {
+            s.env
+                .storage()
+                .persistent()
+                .get_ttl(&(symbol_short!("valid_for"), scope.clone()))
+        })
+    };
+    assert_eq!(ttl_of_period(), ttl::EXTEND_TO);
+
+    // Below the threshold, `bump_contract` extends it again.
+    advance(&s.env, ttl::EXTEND_TO - ttl::THRESHOLD + 1);
+    client.bump_contract();
+    assert_eq!(ttl_of_period(), ttl::EXTEND_TO);
+    assert_eq!(client.validity_period(&scope), Some(100));
+}
+
+#[test]
+fn revoked_attestations_are_not_valid() {
+    let s = setup();
+    let client = s.client();
+    let guardian = Address::generate(&s.env);
+    client.set_dispute_role(&guardian, &Some(DisputeRole::Guardian));
+    let evidence = BytesN::from_array(&s.env, &[1; 32]);
+
+    let upheld = submission(&s.env, 1);
+    let revoked = submission(&s.env, 2);
+    attest(&s, &upheld, "alliance", None);
+    attest(&s, &revoked, "alliance", None);
+
+    // An open dispute does not invalidate an attestation.
+    client.open_dispute(&guardian, &upheld, &evidence);
+    client.open_dispute(&guardian, &revoked, &evidence);
+    assert!(client.is_valid(&upheld));
+    assert!(client.is_valid(&revoked));
+
+    client.resolve_dispute(&guardian, &upheld, &DisputeResolution::Upheld);
+    client.resolve_dispute(&guardian, &revoked, &DisputeResolution::Revoked);
+    assert!(client.is_valid(&upheld));
+    assert!(!client.is_valid(&revoked));
+}
@@ -0,0 +1,37 @@
+use super::{circuit, setup};
+use crate::OptimizationAttestation;
+use ultrahonk_soroban_verifier::{UltraHonkVerifier, VkConstants};
+
+const PROOF: &[u8] = include_bytes!("../../../../api/stellar/proof.bin");
+const PUBLIC_INPUTS: &[u8] = include_bytes!("../../../../api/stellar/public_inputs.bin");
+
+/// The verification half of `verify_and_attest`, on the VK as the
+/// contract stored it: the browser prover's proof verifies, within the
+/// bound `estimate_verify_cost` reports.
+#[test]
+fn stored_vk_verifies_within_the_estimate() {
+    let s = setup();
+    let env = &s.env;
+    let estimate = s.client().estimate_verify_cost(&circuit(env), &(PROOF.len() as u32));
+
+    env.as_contract(&s.id, || {
+        let active = OptimizationAttestation::active_version(env, &circuit(env)).unwrap();
+        let key = active.prepared.key.to_array();
+        let derived = UltraHonkVerifier::new_trusted(env, &key, active.meta());
+        assert_eq!(
+            active.prepared.constants.to_array(),
+            VkConstants::of(derived.get_vk()).unwrap().to_bytes()
+        );
+
+        let mut budget = env.cost_estimate().budget();
+        budget.reset_unlimited();
+        let verifier = active.prepared.verifier(env, active.meta());
+        verifier.verify(env, PROOF, PUBLIC_INPUTS).unwrap();
+        assert!(budget.cpu_instruction_cost() < estimate.cpu_insns);
+        assert!(budget.memory_bytes_cost() < estimate.mem_bytes);
+
+        let mut bad = PROOF.to_vec();
+        bad[100] ^= 1;
+        assert!(verifier.verify(env, &bad, PUBLIC_INPUTS).is_err());
+    });
+}
@@ -0,0 +1,63 @@
+//! Time-to-live of the contract's ledger entries.
+//!
+//! Persistent entries are extended to `EXTEND_TO` ledgers (about a month)
+//! whenever they are written or bumped with less than `THRESHOLD` left, and
+//! are archived by the network once that runs out. An archived attestation
+//! reads like one that was never made, so recording it also writes a small
+//! marker under the same id that is kept alive for the maximum TTL: while
+//! the marker is live, a missing attestation is known to be archived (and
+//! can be restored) rather than absent, and cannot be attested again.
+//!
+//! The instance entry, which holds the admin, the circuit list and each
+//! circuit's latest VK version, is extended the same way on every write
+//! and by `bump_contract`.
+
+use soroban_sdk::{contracttype, symbol_short, BytesN, Env, IntoVal, Symbol, Val};
+
+/// Remaining TTL below which an entry is extended.
+pub const THRESHOLD: u32 = 100_000;
+
+/// TTL an entry is extended to (about 31 days).
+pub const EXTEND_TO: u32 = 535_679;
+
+/// Whether an attestation can be read.
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AttestationPresence {
+    /// Stored and live.
+    Live,
+    /// Recorded, but its entry has been archived and must be restored
+    /// before it can be read.
+    Archived,
+    /// Never recorded, or archived so long ago that its marker expired too.
+    Missing,
+}
+
+fn key_marker(submission_id: &BytesN<32>) -> (Symbol, BytesN<32>) {
+    (symbol_short!("attested"), submission_id.clone())
+}
+
+/// Extend the persistent entry at `key`.
+pub fn extend<K: IntoVal<Env, Val>>(env: &Env, key: &K) {
+    env.storage().persistent().extend_ttl(key, THRESHOLD, EXTEND_TO);
+}
+
+/// Extend the contract instance.
+pub fn extend_instance(env: &Env) {
+    env.storage().instance().extend_ttl(THRESHOLD, EXTEND_TO);
+}
+
+/// Note that `submission_id` has been attested, or keep the note alive.
+pub fn mark_attested(env: &Env, submission_id: &BytesN<32>) {
+    let key = key_marker(submission_id);
+    if !env.storage().persistent().has(&key) {
+        env.storage().persistent().set(&key, &env.ledger().sequence());
+    }
+    let max = env.storage().max_ttl();
+    env.storage().persistent().extend_ttl(&key, EXTEND_TO, max);
+}
+
+/// `true` iff `submission_id` was attested and its marker is still live.
+pub fn was_attested(env: &Env, submission_id: &BytesN<32>) -> bool {
+    env.storage().persistent().has(&key_marker(submission_id))
+}
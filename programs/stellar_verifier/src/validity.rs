@@ -0,0 +1,99 @@
+//! How long attestations count, and how fresh proofs must be.
+//!
+//! The admin may give a circuit or an alliance a validity period: an
+//! attestation recorded under either expires that many ledgers later
+//! (`Attestation::valid_until`), the shorter period winning when both are
+//! set. Separately, a circuit may have a freshness window: its schema then
+//! carries a `FieldType::Ledger` word, the ledger the proof was generated
+//! for, and proofs whose ledger is older than the window are rejected, so
+//! a proof cannot be kept and submitted months later.
+//!
+//! Both are persistent entries, extended whenever they are set or read by
+//! an attestation and by `bump_contract` / `bump_alliance_attestations`.
+
+use crate::ttl;
+use soroban_sdk::{contracttype, symbol_short, Env, IntoVal, String, Symbol, Val};
+use ultrahonk_soroban_verifier::public_inputs;
+
+/// What a validity period applies to.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ValidityScope {
+    Circuit(Symbol),
+    Alliance(String),
+}
+
+fn key_period(scope: &ValidityScope) -> (Symbol, ValidityScope) {
+    (symbol_short!("valid_for"), scope.clone())
+}
+
+fn key_freshness(circuit: &Symbol) -> (Symbol, Symbol) {
+    (symbol_short!("fresh"), circuit.clone())
+}
+
+/// Ledgers attestations under `scope` stay valid, if limited.
+pub fn period(env: &Env, scope: &ValidityScope) -> Option<u32> {
+    env.storage().persistent().get(&key_period(scope))
+}
+
+pub fn set_period(env: &Env, scope: &ValidityScope, ledgers: Option<u32>) {
+    set(env, &key_period(scope), ledgers);
+}
+
+/// Extend the validity period of `scope`, if it has one.
+pub fn extend_period(env: &Env, scope: &ValidityScope) {
+    extend(env, &key_period(scope));
+}
+
+/// Last ledger at which an attestation of `circuit` for `alliance_id`,
+/// recorded now, is valid; `None` if neither has a validity period.
+pub fn valid_until(env: &Env, circuit: &Symbol, alliance_id: &String) -> Option<u32> {
+    let circuit = ValidityScope::Circuit(circuit.clone());
+    let alliance = ValidityScope::Alliance(alliance_id.clone());
+    extend_period(env, &circuit);
+    extend_period(env, &alliance);
+    let by_circuit = period(env, &circuit);
+    let by_alliance = period(env, &alliance);
+    let ledgers = match (by_circuit, by_alliance) {
+        (Some(a), Some(b)) => a.min(b),
+        (a, b) => a.or(b)?,
+    };
+    Some(env.ledger().sequence().saturating_add(ledgers))
+}
+
+/// Maximum age in ledgers of the `Ledger` word in proofs of `circuit`, if
+/// the circuit requires one.
+pub fn freshness(env: &Env, circuit: &Symbol) -> Option<u32> {
+    env.storage().persistent().get(&key_freshness(circuit))
+}
+
+pub fn set_freshness(env: &Env, circuit: &Symbol, ledgers: Option<u32>) {
+    set(env, &key_freshness(circuit), ledgers);
+}
+
+/// Extend the freshness window of `circuit`, if it has one.
+pub fn extend_freshness(env: &Env, circuit: &Symbol) {
+    extend(env, &key_freshness(circuit));
+}
+
+/// `true` iff a proof generated for ledger `anchor` may be submitted now,
+/// at most `max_age` ledgers later.
+pub fn is_fresh(env: &Env, anchor: u32, max_age: u32) -> bool {
+    public_inputs::is_fresh(anchor, env.ledger().sequence(), max_age)
+}
+
+fn set<K: IntoVal<Env, Val>>(env: &Env, key: &K, ledgers: Option<u32>) {
+    match ledgers {
+        Some(ledgers) => {
+            env.storage().persistent().set(key, &ledgers);
+            ttl::extend(env, key);
+        }
+        None => env.storage().persistent().remove(key),
+    }
+}
+
+fn extend<K: IntoVal<Env, Val>>(env: &Env, key: &K) {
+    if env.storage().persistent().has(key) {
+        ttl::extend(env, key);
+    }
+}
@@ -44,6 +44,12 @@ export interface OptimizationLogData {
    * 32-byte metadata hash committed on-chain (should correspond to the off-chain payload).
    */
   metadataHash?: Uint8Array;
+  /**
+   * Proof buffer account holding a Noir proof of encryption for `metadataHash`,
+   * already verified on-chain (`verify_proof_buffer` + `check_proof_pairing`).
+   * The submission closes it.
+   */
+  encryptionProof?: PublicKey;
   // Privacy sponsor integration fields
   lightProtocolProof?: Uint8Array;
   compressionRatio?: number;
@@ -143,10 +149,10 @@ export class BlockchainService {
     // Duration days (must be 1-365)
     const durationDays = Math.min(365, Math.max(1, data.durationDays));
 
-    // Proof of encryption (required, non-empty) - use encrypted baseline as proof
-    const proofOfEncryption = data.encryptedBaseline.length > 0
-      ? Buffer.from(data.encryptedBaseline.slice(0, 64))
-      : Buffer.from([1, 2, 3, 4]); // Fallback minimal proof
+    // Proof of encryption is verified on-chain ahead of time, in its own proof buffer
+    if (!data.encryptionProof) {
+      throw new Error('Missing verified proof of encryption (encryptionProof proof buffer)');
+    }
 
     // Light Protocol proof (required, non-empty)
     const lightProtocolProof = data.lightProtocolProof && data.lightProtocolProof.length > 0
@@ -166,7 +172,6 @@ export class BlockchainService {
       32 + // metadata_hash
       1 + // architecture_category
       2 + // duration_days
-      4 + proofOfEncryption.length + // vec length prefix + data
       4 + lightProtocolProof.length + // vec length prefix + data
       2; // compression_ratio
 
@@ -199,12 +204,6 @@ export class BlockchainService {
     (instructionData as any).writeUInt16LEUnsafe ? (instructionData as any).writeUInt16LE(durationDays, offset) : (instructionData as any).writeUInt16LE(durationDays, offset);
     offset += 2;
 
-    // Write proof_of_encryption as Vec<u8> (4-byte length prefix + bytes)
-    (instructionData as any).writeUInt32LE(proofOfEncryption.length, offset);
-    offset += 4;
-    (proofOfEncryption as any).copy(instructionData, offset);
-    offset += proofOfEncryption.length;
-
     // Write light_protocol_proof as Vec<u8> (4-byte length prefix + bytes)
     (instructionData as any).writeUInt32LE(lightProtocolProof.length, offset);
     offset += 4;
@@ -215,13 +214,15 @@ export class BlockchainService {
     (instructionData as any).writeUInt16LE(compressionRatio, offset);
     offset += 2;
 
-    // Anchor 0.29 SubmitOptimizationLog context declares exactly three accounts:
-    // optimization_log (PDA, init), submitter (signer, mut, payer), system_program.
-    // A previous version passed SYSVAR_RENT_PUBKEY as a 4th account — Anchor rejects
+    // SubmitOptimizationLog context declares exactly four accounts:
+    // optimization_log (PDA, init), encryption_proof (mut, closed to the submitter),
+    // submitter (signer, mut, payer), system_program.
+    // A previous version passed SYSVAR_RENT_PUBKEY as an extra account — Anchor rejects
     // extra accounts, which broke the whole Solana→Stellar dual-chain flow.
     return new TransactionInstruction({
       keys: [
         { pubkey: optimizationLogPda, isSigner: false, isWritable: true },
+        { pubkey: data.encryptionProof, isSigner: false, isWritable: true },
         { pubkey: payer, isSigner: true, isWritable: true },
         { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
       ],